@@ -0,0 +1,311 @@
+//! `digital-bloom` command-line driver
+//!
+//! Creates an engine from a seed, feeds it a scripted (JSON/TOML) or recorded
+//! (`.dbrp`) input sequence, steps it for extra frames and writes stats,
+//! snapshots, SVG or PNG output. Useful for regression fixtures, renders of
+//! bug-report recordings and quick performance checks without a browser.
+
+mod script;
+
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Instant;
+
+use clap::Parser;
+use digital_bloom_core::{
+    AnimationFormat, DigitalBloom, ExportError, ExportOptions, Framebuffer, InputEvent, Recording, RenderOptions,
+    SnapshotError, MAX_FRAME_PIXELS,
+};
+
+use crate::script::{Script, ScriptError};
+
+#[derive(Debug, Parser)]
+#[command(name = "digital-bloom", version, about = "Run the Digital Bloom simulation headlessly")]
+struct Args {
+    /// Script (.json or .toml) or recording (.dbrp) to run; omit for an empty scene
+    input: Option<PathBuf>,
+
+    /// Seed for a new engine, overriding the script's seed
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Fixed 1/60s steps to run after the input
+    #[arg(long, default_value_t = 0)]
+    frames: usize,
+
+    /// Print run statistics as JSON to stdout
+    #[arg(long)]
+    stats: bool,
+
+    /// Write a binary snapshot of the final state
+    #[arg(long, value_name = "PATH")]
+    snapshot: Option<PathBuf>,
+
+    /// Write a JSON snapshot of the final state
+    #[arg(long, value_name = "PATH")]
+    snapshot_json: Option<PathBuf>,
+
+    /// Write the final frame as SVG
+    #[arg(long, value_name = "PATH")]
+    svg: Option<PathBuf>,
+
+    /// Write the final frame as PNG
+    #[arg(long, value_name = "PATH")]
+    png: Option<PathBuf>,
+
+    /// Write a recording of the whole run (replayable with this tool)
+    #[arg(long, value_name = "PATH")]
+    record: Option<PathBuf>,
+
+    /// Write every `--stride`th frame of the run as numbered PNGs
+    #[arg(long, value_name = "DIR")]
+    png_dir: Option<PathBuf>,
+
+    /// Write the run as an animated GIF
+    #[arg(long, value_name = "PATH")]
+    gif: Option<PathBuf>,
+
+    /// Write the run as an animated PNG
+    #[arg(long, value_name = "PATH")]
+    apng: Option<PathBuf>,
+
+    /// Output size as WIDTHxHEIGHT; defaults to the canvas size
+    #[arg(long, value_name = "WxH", value_parser = parse_size)]
+    size: Option<(usize, usize)>,
+
+    /// Frames stepped between written animation frames
+    #[arg(long, default_value_t = 2)]
+    stride: usize,
+}
+
+impl Args {
+    /// Animation exports replay the run, so it has to be recorded
+    fn needs_recording(&self) -> bool {
+        self.record.is_some() || self.png_dir.is_some() || self.gif.is_some() || self.apng.is_some()
+    }
+}
+
+fn parse_size(value: &str) -> Result<(usize, usize), String> {
+    let (width, height) = value.split_once(['x', 'X']).ok_or("expected WIDTHxHEIGHT, e.g. 800x600")?;
+    // GIF stores dimensions as u16, so that is the widest any output can be
+    let parse = |v: &str| match v.trim().parse::<u16>() {
+        Ok(0) | Err(_) => Err(format!("invalid dimension `{v}`, expected 1 to {}", u16::MAX)),
+        Ok(n) => Ok(usize::from(n)),
+    };
+    let (width, height) = (parse(width)?, parse(height)?);
+    if !Framebuffer::fits(width, height) {
+        return Err(format!("{width}x{height} is more than {MAX_FRAME_PIXELS} pixels"));
+    }
+    Ok((width, height))
+}
+
+#[derive(Debug)]
+enum CliError {
+    Io(PathBuf, io::Error),
+    Script(PathBuf, ScriptError),
+    Snapshot(PathBuf, SnapshotError),
+    Export(PathBuf, ExportError),
+    Usage(&'static str),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Io(path, err) => write!(f, "{}: {err}", path.display()),
+            CliError::Script(path, err) => write!(f, "{}: {err}", path.display()),
+            CliError::Snapshot(path, err) => write!(f, "{}: {err}", path.display()),
+            CliError::Export(path, err) => write!(f, "{}: {err}", path.display()),
+            CliError::Usage(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+fn main() -> ExitCode {
+    match run(&Args::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("digital-bloom: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &Args) -> Result<(), CliError> {
+    let started = Instant::now();
+    let (mut bloom, mut frames) = load(args)?;
+
+    let (width, height) = bloom.bounds();
+    for _ in 0..args.frames {
+        bloom.update(width, height);
+    }
+    frames += args.frames as u64;
+    let elapsed = started.elapsed().as_secs_f64();
+
+    let recording = bloom.stop_recording();
+    write_outputs(args, &bloom, recording.as_ref())?;
+
+    if args.stats {
+        let stats = serde_json::json!({
+            "seed": bloom.seed(),
+            "frames": frames,
+            "particles": bloom.particles_len(),
+            "vines": bloom.vines_slice().len(),
+            "grown_vines": bloom.grown_vines_slice().len(),
+            "lightnings": bloom.lightnings_slice().len(),
+            "scene_load": bloom.scene_load(),
+            "elapsed_seconds": elapsed,
+            "frames_per_second": if elapsed > 0.0 { frames as f64 / elapsed } else { 0.0 },
+        });
+        println!("{stats:#}");
+    }
+    Ok(())
+}
+
+/// Build the engine from the input file, recording if any output needs it
+///
+/// Returns the engine and the number of frames (update calls) run so far.
+fn load(args: &Args) -> Result<(DigitalBloom, u64), CliError> {
+    let Some(path) = &args.input else {
+        let mut bloom = args.seed.map(DigitalBloom::with_seed).unwrap_or_default();
+        if args.needs_recording() {
+            bloom.start_recording();
+        }
+        return Ok((bloom, 0));
+    };
+
+    let bytes = fs::read(path).map_err(|err| CliError::Io(path.clone(), err))?;
+    if bytes.starts_with(b"DBRP") {
+        if args.seed.is_some() {
+            return Err(CliError::Usage("--seed can't be combined with a recording; it already fixes the seed"));
+        }
+        let recording = Recording::from_bytes(&bytes).map_err(|err| CliError::Snapshot(path.clone(), err))?;
+        let frames = recording
+            .events()
+            .iter()
+            .filter(|event| matches!(event, InputEvent::Update { .. } | InputEvent::UpdateDt { .. }))
+            .count() as u64;
+        let bloom = if args.needs_recording() { recording.resume() } else { recording.replay() };
+        return Ok((bloom.map_err(|err| CliError::Snapshot(path.clone(), err))?, frames));
+    }
+
+    let text = String::from_utf8(bytes)
+        .map_err(|_| CliError::Io(path.clone(), io::Error::new(io::ErrorKind::InvalidData, "script is not UTF-8")))?;
+    let is_toml = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("toml"));
+    let script = if is_toml { Script::from_toml(&text) } else { Script::from_json(&text) }
+        .map_err(|err| CliError::Script(path.clone(), err))?;
+
+    let mut bloom = args.seed.or(script.seed).map(DigitalBloom::with_seed).unwrap_or_default();
+    if args.needs_recording() {
+        bloom.start_recording();
+    }
+    let frames = script.run(&mut bloom).map_err(|err| CliError::Script(path.clone(), err))?;
+    Ok((bloom, frames))
+}
+
+fn write_outputs(args: &Args, bloom: &DigitalBloom, recording: Option<&Recording>) -> Result<(), CliError> {
+    let (canvas_width, canvas_height) = bloom.bounds();
+    let (width, height) = args.size.unwrap_or((canvas_width.round() as usize, canvas_height.round() as usize));
+
+    if let Some(path) = &args.snapshot {
+        write_file(path, bloom.snapshot())?;
+    }
+    if let Some(path) = &args.snapshot_json {
+        write_file(path, bloom.snapshot_json())?;
+    }
+    if let Some(path) = &args.svg {
+        write_file(path, bloom.to_svg(width as f64, height as f64))?;
+    }
+    if let Some(path) = &args.png {
+        let frame = bloom
+            .render(width, height, &RenderOptions::default())
+            .ok_or_else(|| CliError::Export(path.clone(), ExportError::InvalidSize))?;
+        let file = create_file(path)?;
+        frame.write_png(file).map_err(|err| CliError::Export(path.clone(), err))?;
+    }
+
+    // Only `load` decides whether to record, and it does whenever these are set
+    let Some(recording) = recording else {
+        return Ok(());
+    };
+    if let Some(path) = &args.record {
+        write_file(path, recording.to_bytes())?;
+    }
+
+    let options = ExportOptions {
+        width,
+        height,
+        // Write every frame the recording has
+        frames: usize::MAX,
+        frame_stride: args.stride,
+        render: RenderOptions::default(),
+    };
+    if let Some(dir) = &args.png_dir {
+        recording.export_png_sequence(dir, &options).map_err(|err| CliError::Export(dir.clone(), err))?;
+    }
+    for (path, format) in [(&args.gif, AnimationFormat::Gif), (&args.apng, AnimationFormat::Apng)] {
+        if let Some(path) = path {
+            let file = create_file(path)?;
+            recording.export_animation(format, file, &options).map_err(|err| CliError::Export(path.clone(), err))?;
+        }
+    }
+    Ok(())
+}
+
+fn create_file(path: &Path) -> Result<BufWriter<File>, CliError> {
+    File::create(path).map(BufWriter::new).map_err(|err| CliError::Io(path.to_path_buf(), err))
+}
+
+fn write_file(path: &Path, contents: impl AsRef<[u8]>) -> Result<(), CliError> {
+    fs::write(path, contents).map_err(|err| CliError::Io(path.to_path_buf(), err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("800x600"), Ok((800, 600)));
+        assert_eq!(parse_size("64X32"), Ok((64, 32)));
+        assert!(parse_size("800").is_err());
+        assert!(parse_size("0x10").is_err());
+        assert!(parse_size("4294967295x4294967295").is_err());
+        assert!(parse_size("65535x65535").is_err());
+        assert_eq!(parse_size("8192x8192"), Ok((8192, 8192)));
+    }
+
+    #[test]
+    fn test_recorded_run_resumes_from_file() {
+        let dir = std::env::temp_dir().join(format!("digital-bloom-cli-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("scene.json");
+        let recording = dir.join("scene.dbrp");
+        fs::write(&script, r#"{"seed": 3, "steps": [{"action": "create", "mode": "Burst", "x": 200, "y": 200}]}"#).unwrap();
+
+        let first = Args::parse_from(["digital-bloom", script.to_str().unwrap(), "--frames", "5", "--record", recording.to_str().unwrap()]);
+        run(&first).unwrap();
+
+        // Replaying the recording and stepping further matches one longer run
+        let resumed = Args::parse_from(["digital-bloom", recording.to_str().unwrap(), "--frames", "5"]);
+        let (mut bloom, frames) = load(&resumed).unwrap();
+        assert_eq!(frames, 5);
+        let longer = Args::parse_from(["digital-bloom", script.to_str().unwrap()]);
+        let (mut expected, _) = load(&longer).unwrap();
+        for _ in 0..5 {
+            bloom.update(400.0, 400.0);
+        }
+        for _ in 0..10 {
+            expected.update(400.0, 400.0);
+        }
+        assert_eq!(bloom.snapshot(), expected.snapshot());
+
+        let with_seed = Args::parse_from(["digital-bloom", recording.to_str().unwrap(), "--seed", "1"]);
+        assert!(matches!(load(&with_seed), Err(CliError::Usage(_))));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,266 @@
+//! Scripted input sequences
+//!
+//! A script sets up the engine (canvas size, physics, palette, budget) and
+//! then lists the calls to make on it, in order. The same structure is read
+//! from JSON or TOML:
+//!
+//! ```toml
+//! seed = 42
+//! palette = "ocean"
+//! budget = { type = "hard_cap", max = 800 }
+//!
+//! [[steps]]
+//! action = "create"
+//! mode = "Burst"
+//! x = 200
+//! y = 200
+//! count = 40
+//!
+//! [[steps]]
+//! action = "update"
+//! frames = 120
+//! ```
+
+use std::fmt;
+
+use digital_bloom_core::{BudgetPolicy, DigitalBloom, EngineConfig, Palette, PaletteError, ParticleMode};
+use serde::Deserialize;
+
+/// Engine setup plus the calls to make on it
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Script {
+    /// Seed for the engine; `--seed` takes precedence
+    pub seed: Option<u64>,
+    /// Canvas size the steps run on
+    pub width: f64,
+    pub height: f64,
+    pub config: Option<EngineConfig>,
+    pub palette: Option<PaletteSpec>,
+    pub budget: Option<BudgetPolicy>,
+    pub steps: Vec<Step>,
+}
+
+impl Default for Script {
+    fn default() -> Self {
+        Script {
+            seed: None,
+            // Same default canvas as the engine
+            width: 400.0,
+            height: 400.0,
+            config: None,
+            palette: None,
+            budget: None,
+            steps: Vec::new(),
+        }
+    }
+}
+
+/// Either a builtin palette name or an explicit color list
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum PaletteSpec {
+    Builtin(String),
+    Custom(Palette),
+}
+
+impl PaletteSpec {
+    pub fn resolve(&self) -> Result<Palette, PaletteError> {
+        match self {
+            PaletteSpec::Builtin(name) => Palette::builtin(name),
+            PaletteSpec::Custom(palette) => Ok(palette.clone()),
+        }
+    }
+}
+
+/// One call on the engine
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Step {
+    /// Spawn elements for a brush mode, like a tap on the canvas
+    Create {
+        mode: ParticleMode,
+        x: f64,
+        y: f64,
+        #[serde(default = "default_count")]
+        count: usize,
+        #[serde(default = "default_size")]
+        size: f64,
+    },
+    /// Advance `frames` fixed steps on the script's canvas
+    Update {
+        #[serde(default = "default_frames")]
+        frames: usize,
+    },
+    /// Advance by wall-clock time, like a host frame callback
+    UpdateDt { seconds: f64 },
+    /// Resize the canvas for the following steps
+    Bounds { width: f64, height: f64 },
+    Clear,
+    Palette { palette: PaletteSpec },
+    Budget { policy: BudgetPolicy },
+}
+
+fn default_count() -> usize {
+    20
+}
+
+fn default_size() -> f64 {
+    4.0
+}
+
+fn default_frames() -> usize {
+    1
+}
+
+#[derive(Debug)]
+pub enum ScriptError {
+    Json(serde_json::Error),
+    Toml(toml::de::Error),
+    Palette(PaletteError),
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScriptError::Json(err) => write!(f, "invalid JSON script: {err}"),
+            ScriptError::Toml(err) => write!(f, "invalid TOML script: {err}"),
+            ScriptError::Palette(err) => write!(f, "invalid palette: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+impl From<PaletteError> for ScriptError {
+    fn from(err: PaletteError) -> Self {
+        ScriptError::Palette(err)
+    }
+}
+
+impl Script {
+    pub fn from_json(text: &str) -> Result<Self, ScriptError> {
+        serde_json::from_str(text).map_err(ScriptError::Json)
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, ScriptError> {
+        toml::from_str(text).map_err(ScriptError::Toml)
+    }
+
+    /// Configure `bloom` and make every scripted call on it
+    ///
+    /// Returns the number of frames run, counting each `update_dt` call as one.
+    pub fn run(&self, bloom: &mut DigitalBloom) -> Result<u64, ScriptError> {
+        // Resolve up front so a bad palette fails before anything runs
+        let palette = self.palette.as_ref().map(PaletteSpec::resolve).transpose()?;
+
+        bloom.set_bounds(self.width, self.height);
+        if let Some(config) = &self.config {
+            bloom.set_config(config.clone());
+        }
+        if let Some(palette) = palette {
+            bloom.set_palette(palette);
+        }
+        if let Some(policy) = self.budget {
+            bloom.set_budget_policy(policy);
+        }
+
+        let mut frames = 0;
+        for step in &self.steps {
+            match step {
+                Step::Create { mode, x, y, count, size } => bloom.create(*mode, *x, *y, *count, *size),
+                Step::Update { frames: count } => {
+                    let (width, height) = bloom.bounds();
+                    for _ in 0..*count {
+                        bloom.update(width, height);
+                    }
+                    frames += *count as u64;
+                }
+                Step::UpdateDt { seconds } => {
+                    bloom.update_dt(*seconds);
+                    frames += 1;
+                }
+                Step::Bounds { width, height } => bloom.set_bounds(*width, *height),
+                Step::Clear => bloom.clear(),
+                Step::Palette { palette } => bloom.set_palette(palette.resolve()?),
+                Step::Budget { policy } => bloom.set_budget_policy(*policy),
+            }
+        }
+        Ok(frames)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOML: &str = r#"
+seed = 7
+width = 300
+height = 200
+palette = "ocean"
+budget = { type = "hard_cap", max = 30 }
+
+[config]
+gravity = 0.5
+
+[[steps]]
+action = "create"
+mode = "Burst"
+x = 150
+y = 100
+count = 50
+
+[[steps]]
+action = "update"
+frames = 10
+
+[[steps]]
+action = "create"
+mode = "Vine"
+x = 20
+y = 20
+"#;
+
+    #[test]
+    fn test_toml_and_json_scripts_match() {
+        let script = Script::from_toml(TOML).unwrap();
+        assert_eq!(script.seed, Some(7));
+        assert_eq!(script.config.as_ref().unwrap().gravity, 0.5);
+        assert_eq!(script.steps.len(), 3);
+        assert_eq!(script.steps[2], Step::Create { mode: ParticleMode::Vine, x: 20.0, y: 20.0, count: 20, size: 4.0 });
+
+        let json = r#"{
+            "seed": 7, "width": 300, "height": 200, "palette": "ocean",
+            "budget": {"type": "hard_cap", "max": 30},
+            "config": {"gravity": 0.5},
+            "steps": [
+                {"action": "create", "mode": "Burst", "x": 150, "y": 100, "count": 50},
+                {"action": "update", "frames": 10},
+                {"action": "create", "mode": "Vine", "x": 20, "y": 20}
+            ]
+        }"#;
+        assert_eq!(Script::from_json(json).unwrap(), script);
+    }
+
+    #[test]
+    fn test_run_applies_setup_and_steps() {
+        let script = Script::from_toml(TOML).unwrap();
+        let mut bloom = DigitalBloom::with_seed(7);
+        assert_eq!(script.run(&mut bloom).unwrap(), 10);
+        assert_eq!(bloom.bounds(), (300.0, 200.0));
+        assert_eq!(bloom.palette(), &Palette::builtin("ocean").unwrap());
+        assert!(bloom.particles_len() <= 30);
+        assert_eq!(bloom.vines_slice().len(), 1);
+    }
+
+    #[test]
+    fn test_rejects_bad_input() {
+        assert!(matches!(Script::from_json(r#"{"steps": [{"action": "explode"}]}"#), Err(ScriptError::Json(_))));
+        assert!(matches!(Script::from_toml("frames = 3"), Err(ScriptError::Toml(_))));
+
+        let script = Script::from_json(r#"{"palette": "neon"}"#).unwrap();
+        let mut bloom = DigitalBloom::with_seed(1);
+        assert!(matches!(script.run(&mut bloom), Err(ScriptError::Palette(_))));
+    }
+}
@@ -0,0 +1,77 @@
+//! Scenes shared by the benchmarks
+//!
+//! Every scene is built from a fixed seed, so each commit benchmarks exactly
+//! the same work. To compare a change against the current tree:
+//!
+//! ```text
+//! git stash && cargo bench --all-features -- --save-baseline before
+//! git stash pop && cargo bench --all-features -- --baseline before
+//! ```
+
+// Each bench binary uses its own subset of these helpers
+#![allow(dead_code)]
+
+use digital_bloom_core::{BudgetPolicy, DigitalBloom, ParticleMode};
+
+pub const WIDTH: f64 = 400.0;
+pub const HEIGHT: f64 = 400.0;
+
+pub const STEADY_PARTICLES: usize = 800;
+pub const STEADY_VINES: usize = 50;
+pub const STEADY_BOLTS: usize = 20;
+
+/// Modes that spawn plain particles, cycled through when filling a scene
+pub const PARTICLE_MODES: [ParticleMode; 5] = [
+    ParticleMode::Gravity,
+    ParticleMode::Bounce,
+    ParticleMode::Burst,
+    ParticleMode::Constellation,
+    ParticleMode::Vortex,
+];
+
+/// Point `i` of a 10-column grid spread over the canvas
+fn grid(i: usize) -> (f64, f64) {
+    (20.0 + (i % 10) as f64 * 36.0, 20.0 + (i / 10 % 10) as f64 * 36.0)
+}
+
+/// A busy screen: 800 particles over every particle mode, 50 vines and 20 bolts
+///
+/// The vines have grown for half a second so they carry real point lists.
+/// `policy` is applied once the scene is full (a lower cap trims it right
+/// away); pass `HardCap { max: 800 }` to keep every particle, or
+/// `DynamicDecay` to match the watch build.
+pub fn steady_state(policy: BudgetPolicy) -> DigitalBloom {
+    let mut bloom = DigitalBloom::with_seed(0x5EED);
+    bloom.set_bounds(WIDTH, HEIGHT);
+    bloom.set_budget_policy(BudgetPolicy::None);
+
+    for i in 0..STEADY_VINES {
+        // Start near the middle so no vine runs off the canvas while growing
+        let (x, y) = grid(i);
+        bloom.create_vine(100.0 + x * 0.5, 100.0 + y * 0.5, 4.0);
+    }
+    for _ in 0..30 {
+        bloom.update(WIDTH, HEIGHT);
+    }
+
+    let per_spawn = 20;
+    for i in 0..STEADY_PARTICLES / per_spawn {
+        let (x, y) = grid(i);
+        bloom.create(PARTICLE_MODES[i % PARTICLE_MODES.len()], x, y, per_spawn, 4.0);
+    }
+    for i in 0..STEADY_BOLTS {
+        let (x, _) = grid(i);
+        bloom.create_lightning(x, 10.0, WIDTH, HEIGHT);
+    }
+
+    assert_eq!(bloom.vines_slice().len(), STEADY_VINES);
+    assert_eq!(bloom.lightnings_slice().len(), STEADY_BOLTS);
+    assert!(bloom.particles_len() >= STEADY_PARTICLES);
+    bloom.set_budget_policy(policy);
+    bloom
+}
+
+/// Independent copy of a scene, for benchmarks that mutate it
+pub fn fork(snapshot: &[u8]) -> DigitalBloom {
+    DigitalBloom::from_snapshot(snapshot).unwrap()
+}
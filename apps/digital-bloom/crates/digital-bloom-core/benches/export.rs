@@ -0,0 +1,37 @@
+//! Exporting the steady-state scene: snapshots, SVG and rasterized frames
+
+mod common;
+
+use common::{steady_state, HEIGHT, WIDTH};
+use criterion::{criterion_group, criterion_main, Criterion};
+use digital_bloom_core::{BudgetPolicy, DigitalBloom, Framebuffer, RenderOptions};
+
+fn snapshots(c: &mut Criterion) {
+    let scene = steady_state(BudgetPolicy::HardCap { max: 800 });
+    let bytes = scene.snapshot();
+    let json = scene.snapshot_json();
+
+    let mut group = c.benchmark_group("snapshot");
+    group.bench_function("binary", |b| b.iter(|| scene.snapshot()));
+    group.bench_function("binary_restore", |b| b.iter(|| DigitalBloom::from_snapshot(&bytes).unwrap()));
+    group.bench_function("json", |b| b.iter(|| scene.snapshot_json()));
+    group.bench_function("json_restore", |b| b.iter(|| DigitalBloom::from_snapshot_json(&json).unwrap()));
+    group.finish();
+}
+
+fn frames(c: &mut Criterion) {
+    let scene = steady_state(BudgetPolicy::HardCap { max: 800 });
+    let mut group = c.benchmark_group("frame");
+    group.bench_function("svg", |b| b.iter(|| scene.to_svg(WIDTH, HEIGHT)));
+
+    let mut framebuffer = Framebuffer::new(WIDTH as usize, HEIGHT as usize);
+    let glow = RenderOptions::default();
+    let plain = RenderOptions { glow: 0.0, ..RenderOptions::default() };
+    group.bench_function("render", |b| b.iter(|| scene.render_into(&mut framebuffer, &glow)));
+    group.bench_function("render_no_glow", |b| b.iter(|| scene.render_into(&mut framebuffer, &plain)));
+    group.bench_function("png", |b| b.iter(|| framebuffer.write_png(Vec::new()).unwrap()));
+    group.finish();
+}
+
+criterion_group!(benches, snapshots, frames);
+criterion_main!(benches);
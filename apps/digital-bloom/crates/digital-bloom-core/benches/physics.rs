@@ -0,0 +1,161 @@
+//! Simulation hot paths: stepping a busy scene, spawning, bolt generation and
+//! neighbour queries
+
+mod common;
+
+use common::{fork, steady_state, HEIGHT, PARTICLE_MODES, STEADY_PARTICLES, WIDTH};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use digital_bloom_core::{BudgetPolicy, DigitalBloom, EngineConfig, Lightning, ParticleMode, Rgba, Rng};
+
+/// One fixed step of the steady-state scene, per budget policy
+fn update(c: &mut Criterion) {
+    let mut group = c.benchmark_group("update");
+    let policies = [
+        ("hard_cap", BudgetPolicy::HardCap { max: 800 }),
+        ("dynamic_decay", BudgetPolicy::DynamicDecay),
+        ("random_cull", BudgetPolicy::RandomCull { max: 500 }),
+    ];
+    for (name, policy) in policies {
+        let scene = steady_state(policy);
+        let snapshot = scene.snapshot();
+        group.throughput(Throughput::Elements(scene.scene_load() as u64));
+        // Step a fresh copy each time so the scene doesn't decay while measuring
+        group.bench_function(BenchmarkId::new("steady_state", name), |b| {
+            b.iter_batched(
+                || fork(&snapshot),
+                |mut bloom| {
+                    bloom.update(WIDTH, HEIGHT);
+                    bloom
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+
+    // A full second of frames, so vine growth and bolt expiry are included
+    let snapshot = steady_state(BudgetPolicy::DynamicDecay).snapshot();
+    group.throughput(Throughput::Elements(60));
+    group.bench_function("steady_state_60_frames", |b| {
+        b.iter_batched(
+            || fork(&snapshot),
+            |mut bloom| {
+                for _ in 0..60 {
+                    bloom.update_dt(1.0 / 60.0);
+                }
+                bloom
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+/// Spawning into an empty engine, per mode, and a tap storm into a full one
+fn spawn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("spawn");
+    let count = 100;
+    group.throughput(Throughput::Elements(count as u64));
+    for mode in PARTICLE_MODES {
+        group.bench_function(BenchmarkId::new("create_particles", format!("{mode:?}")), |b| {
+            b.iter_batched(
+                || {
+                    let mut bloom = DigitalBloom::with_seed(1);
+                    bloom.set_budget_policy(BudgetPolicy::None);
+                    bloom
+                },
+                |mut bloom| {
+                    bloom.create(mode, 200.0, 200.0, count, 4.0);
+                    bloom
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.bench_function(BenchmarkId::new("create", "Vine"), |b| {
+        b.iter_batched(
+            || DigitalBloom::with_seed(1),
+            |mut bloom| {
+                bloom.create(ParticleMode::Vine, 200.0, 200.0, 1, 4.0);
+                bloom
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    // 20 bursts of 50 landing on a capped, already full scene
+    let snapshot = steady_state(BudgetPolicy::HardCap { max: 800 }).snapshot();
+    group.throughput(Throughput::Elements(20 * 50));
+    for (name, policy) in [
+        ("hard_cap", BudgetPolicy::HardCap { max: 800 }),
+        ("random_cull", BudgetPolicy::RandomCull { max: 800 }),
+        ("smallest_first", BudgetPolicy::SmallestFirst { max: 800 }),
+    ] {
+        group.bench_function(BenchmarkId::new("storm", name), |b| {
+            b.iter_batched(
+                || {
+                    let mut bloom = fork(&snapshot);
+                    bloom.set_budget_policy(policy);
+                    bloom
+                },
+                |mut bloom| {
+                    for i in 0..20 {
+                        bloom.create_particles_burst(20.0 + i as f64 * 18.0, 200.0, 50, 4.0);
+                    }
+                    bloom
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+/// Generating one bolt with its branches
+fn lightning(c: &mut Criterion) {
+    let config = EngineConfig::default();
+    let mut rng = Rng::new(7);
+    c.bench_function("lightning_new", |b| {
+        b.iter(|| Lightning::new(&mut rng, &config, 200.0, 10.0, 260.0, 300.0, Rgba::WHITE))
+    });
+}
+
+/// Radius queries on the steady-state scene, and a large flock stepping on them
+fn neighbours(c: &mut Criterion) {
+    let mut group = c.benchmark_group("neighbours");
+    let mut scene = steady_state(BudgetPolicy::HardCap { max: 800 });
+    // One step builds the spatial grid
+    scene.update(WIDTH, HEIGHT);
+    let mut found = Vec::new();
+    for radius in [20.0, 80.0] {
+        group.bench_function(BenchmarkId::new("query_radius", radius), |b| {
+            b.iter(|| {
+                found.clear();
+                scene.particles().query_radius(200.0, 200.0, radius, &mut found);
+                found.len()
+            })
+        });
+    }
+
+    let mut flock = DigitalBloom::with_seed(1);
+    flock.set_budget_policy(BudgetPolicy::None);
+    for i in 0..STEADY_PARTICLES / 100 {
+        let (x, y) = (100.0 + (i % 4) as f64 * 70.0, 150.0 + (i / 4) as f64 * 100.0);
+        flock.create(ParticleMode::Boids, x, y, 100, 4.0);
+    }
+    let snapshot = flock.snapshot();
+    group.throughput(Throughput::Elements(STEADY_PARTICLES as u64));
+    group.bench_function("boids_step", |b| {
+        b.iter_batched(
+            || fork(&snapshot),
+            |mut bloom| {
+                bloom.update(WIDTH, HEIGHT);
+                bloom
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, update, spawn, lightning, neighbours);
+criterion_main!(benches);
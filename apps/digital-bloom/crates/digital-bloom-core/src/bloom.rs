@@ -0,0 +1,808 @@
+use std::f64::consts::PI;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::budget::BudgetPolicy;
+use crate::color::Rgba;
+use crate::config::EngineConfig;
+use crate::lightning::Lightning;
+use crate::link::Link;
+use crate::mode::ParticleMode;
+use crate::noise;
+use crate::palette::Palette;
+use crate::particle::{Charge, Particle, ParticleStore, StepContext};
+use crate::replay::{InputEvent, Recording};
+use crate::rng::{entropy_seed, Rng};
+use crate::vine::Vine;
+use crate::Point;
+
+/// Length of one simulation step in seconds
+///
+/// All per-step physics constants were tuned for a 60Hz display.
+pub const FIXED_TIMESTEP: f64 = 1.0 / 60.0;
+
+/// Most particles one `create` call spawns; larger counts are clamped
+///
+/// Recordings with a larger count are rejected on load, so a corrupt file
+/// can't ask for billions of particles before the budget trims them.
+pub const MAX_SPAWN_COUNT: usize = 10_000;
+
+/// Longest frame `update_dt` will simulate, so a stalled host doesn't trigger a catch-up storm
+const MAX_FRAME_TIME: f64 = 0.25;
+
+/// Faded vines kept for reuse by `create_vine`; more than this are freed
+const VINE_POOL_SIZE: usize = 64;
+
+/// Slack for float rounding so e.g. two half-steps still add up to a full step
+const STEP_EPSILON: f64 = 1e-9;
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DigitalBloom {
+    pub(crate) vines: Vec<Vine>,
+    pub(crate) grown_vines: Vec<Vine>,
+    pub(crate) particles: ParticleStore,
+    pub(crate) lightnings: Vec<Lightning>,
+    pub(crate) palette: Palette,
+    pub(crate) config: EngineConfig,
+    pub(crate) budget: BudgetPolicy,
+    pub(crate) max_lightnings: usize,
+    pub(crate) rng: Rng,
+    pub(crate) seed: u64,
+    // Canvas size, set by `update` or `set_bounds`
+    pub(crate) width: f64,
+    pub(crate) height: f64,
+    // Unsimulated time carried over between `update_dt` calls
+    pub(crate) accumulator: f64,
+    // Fixed steps simulated so far; the smoke field evolves with it
+    pub(crate) steps: u64,
+    // Point boids are drawn toward, set by `set_pointer`
+    pub(crate) pointer: Option<Point>,
+    // Call log while `start_recording` is active; never part of a snapshot
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) recording: Option<Recording>,
+    // Faded vines whose point buffers new vines reuse; never part of a snapshot
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) vine_pool: Vec<Vine>,
+    // Constellation links from the last step; recomputed, never part of a snapshot
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) links: Vec<Link>,
+    // Reused neighbour buffer for `relink`
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) link_scratch: Vec<usize>,
+}
+
+impl Default for DigitalBloom {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DigitalBloom {
+    /// Create an engine with a fresh random seed
+    pub fn new() -> Self {
+        Self::with_seed(entropy_seed())
+    }
+
+    /// Create an engine with custom physics and a fresh random seed
+    pub fn with_config(config: EngineConfig) -> Self {
+        let mut bloom = Self::new();
+        bloom.set_config(config);
+        bloom
+    }
+
+    /// Create an engine whose animation is fully determined by `seed`
+    pub fn with_seed(seed: u64) -> Self {
+        DigitalBloom {
+            vines: Vec::new(),
+            grown_vines: Vec::new(),
+            particles: ParticleStore::default(),
+            lightnings: Vec::new(),
+            palette: Palette::default(),
+            config: EngineConfig::default(),
+            budget: BudgetPolicy::default(),
+            max_lightnings: 20,
+            rng: Rng::new(seed),
+            seed,
+            width: 400.0,
+            height: 400.0,
+            accumulator: 0.0,
+            steps: 0,
+            pointer: None,
+            recording: None,
+            vine_pool: Vec::new(),
+            links: Vec::new(),
+            link_scratch: Vec::new(),
+        }
+    }
+
+    fn random_color(&mut self) -> Rgba {
+        self.palette.pick(&mut self.rng)
+    }
+
+    /// Spawn elements for any brush mode at (x, y)
+    ///
+    /// `count` is ignored by the vine and lightning modes, and clamped to
+    /// `MAX_SPAWN_COUNT` by the rest.
+    pub fn create(&mut self, mode: ParticleMode, x: f64, y: f64, count: usize, size: f64) {
+        let count = count.min(MAX_SPAWN_COUNT);
+        match mode {
+            ParticleMode::Vine => self.create_vine(x, y, size),
+            ParticleMode::Gravity => self.create_particles_gravity(x, y, count, size),
+            ParticleMode::Bounce => self.create_particles_bounce(x, y, count, size),
+            ParticleMode::Burst => self.create_particles_burst(x, y, count, size),
+            ParticleMode::Lightning => self.create_lightning(x, y, self.width, self.height),
+            ParticleMode::Constellation => self.create_particles_constellation(x, y, count, size),
+            ParticleMode::Vortex => self.create_particles_vortex(x, y, count, size),
+            ParticleMode::Fireworks => self.create_particles_fireworks(x, y, count, size),
+            ParticleMode::Smoke => self.create_particles_smoke(x, y, count, size),
+            ParticleMode::Boids => self.create_particles_boids(x, y, count, size),
+        }
+    }
+
+    pub fn create_vine(&mut self, x: f64, y: f64, size: f64) {
+        self.record(InputEvent::Vine { x, y, size });
+        let color = self.random_color();
+        let (max_length, min_length) = (self.config.vine_max_length, self.config.vine_min_length);
+        let vine = match self.vine_pool.pop() {
+            Some(mut vine) => {
+                vine.reset(&mut self.rng, Point { x, y }, color, size, max_length, min_length);
+                vine
+            }
+            None => Vine::new(&mut self.rng, x, y, color, size, max_length, min_length),
+        };
+        self.vines.push(vine);
+    }
+
+    pub fn create_particles_gravity(&mut self, x: f64, y: f64, count: usize, size: f64) {
+        let count = count.min(MAX_SPAWN_COUNT);
+        self.record(InputEvent::Particles { mode: ParticleMode::Gravity, x, y, count, size });
+        let color = self.random_color();
+        for _ in 0..count {
+            let vx = (self.rng.next_f64() - 0.5) * 4.0;
+            let vy = -self.rng.next_f64() * 5.0 - 2.0;
+            let particle = Particle::new(x, y, vx, vy, color, size * 0.5, ParticleMode::Gravity);
+            self.particles.push(particle, &self.config);
+        }
+        self.limit_particles();
+    }
+
+    pub fn create_particles_burst(&mut self, x: f64, y: f64, count: usize, size: f64) {
+        let count = count.min(MAX_SPAWN_COUNT);
+        self.record(InputEvent::Particles { mode: ParticleMode::Burst, x, y, count, size });
+        let color = self.random_color();
+        for i in 0..count {
+            let angle = (PI * 2.0 / count as f64) * i as f64;
+            let speed = self.rng.next_f64() * 6.0 + 2.0;
+            let vx = angle.cos() * speed;
+            let vy = angle.sin() * speed;
+            let particle_size = size * (self.rng.next_f64() * 0.5 + 0.5) * 0.5;
+            let particle = Particle::new(x, y, vx, vy, color, particle_size, ParticleMode::Burst);
+            self.particles.push(particle, &self.config);
+        }
+        self.limit_particles();
+    }
+
+    pub fn create_particles_bounce(&mut self, x: f64, y: f64, count: usize, size: f64) {
+        let count = count.min(MAX_SPAWN_COUNT);
+        self.record(InputEvent::Particles { mode: ParticleMode::Bounce, x, y, count, size });
+        let color = self.random_color();
+        for _ in 0..count {
+            let vx = (self.rng.next_f64() - 0.5) * 8.0;
+            let vy = (self.rng.next_f64() - 0.5) * 8.0;
+            let particle = Particle::new(x, y, vx, vy, color, size * 0.5, ParticleMode::Bounce);
+            self.particles.push(particle, &self.config);
+        }
+        self.limit_particles();
+    }
+
+    pub fn create_particles_constellation(&mut self, x: f64, y: f64, count: usize, size: f64) {
+        let count = count.min(MAX_SPAWN_COUNT);
+        self.record(InputEvent::Particles { mode: ParticleMode::Constellation, x, y, count, size });
+        let color = self.random_color();
+        for _ in 0..count {
+            let offset_x = (self.rng.next_f64() - 0.5) * 60.0;
+            let offset_y = (self.rng.next_f64() - 0.5) * 60.0;
+            let vx = (self.rng.next_f64() - 0.5) * 0.5;
+            let vy = (self.rng.next_f64() - 0.5) * 0.5;
+            let particle_size = size * (self.rng.next_f64() * 0.5 + 0.6);
+            let particle = Particle::new(x + offset_x, y + offset_y, vx, vy, color, particle_size, ParticleMode::Constellation);
+            self.particles.push(particle, &self.config);
+        }
+        self.limit_particles();
+    }
+
+    pub fn create_particles_vortex(&mut self, x: f64, y: f64, count: usize, size: f64) {
+        let count = count.min(MAX_SPAWN_COUNT);
+        self.record(InputEvent::Particles { mode: ParticleMode::Vortex, x, y, count, size });
+        let color = self.random_color();
+        for i in 0..count {
+            let angle = (PI * 2.0 / count as f64) * i as f64 + self.rng.next_f64() * 0.5;
+            let radius = self.rng.next_f64() * 80.0 + 40.0;
+            let particle = Particle::new_vortex(x, y, angle, radius, color, size * 0.6);
+            self.particles.push(particle, &self.config);
+        }
+        self.limit_particles();
+    }
+
+    /// Launch a shell from (x, y) that bursts into `count` sparks at the top of its climb
+    ///
+    /// Some sparks crackle into small sub-bursts of their own before fading.
+    pub fn create_particles_fireworks(&mut self, x: f64, y: f64, count: usize, size: f64) {
+        let count = count.min(MAX_SPAWN_COUNT);
+        self.record(InputEvent::Particles { mode: ParticleMode::Fireworks, x, y, count, size });
+        let color = self.random_color();
+        let vx = (self.rng.next_f64() - 0.5) * 1.5;
+        let vy = -self.rng.next_f64() * 3.0 - 7.0;
+        let sparks = count as u32;
+        let shell = Particle::new(x, y, vx, vy, color, size * 0.5, ParticleMode::Fireworks).with_charge(Charge::Shell { sparks });
+        self.particles.push(shell, &self.config);
+        self.limit_particles();
+    }
+
+    /// Release soft puffs that drift through an evolving curl-noise field, growing as they fade
+    pub fn create_particles_smoke(&mut self, x: f64, y: f64, count: usize, size: f64) {
+        let count = count.min(MAX_SPAWN_COUNT);
+        self.record(InputEvent::Particles { mode: ParticleMode::Smoke, x, y, count, size });
+        let color = self.random_color();
+        for _ in 0..count {
+            let offset_x = (self.rng.next_f64() - 0.5) * size * 4.0;
+            let offset_y = (self.rng.next_f64() - 0.5) * size * 4.0;
+            let vx = (self.rng.next_f64() - 0.5) * 0.5;
+            let vy = -self.rng.next_f64() * 0.5;
+            let puff_size = size * (self.rng.next_f64() * 0.5 + 0.5);
+            let particle = Particle::new(x + offset_x, y + offset_y, vx, vy, color, puff_size, ParticleMode::Smoke);
+            self.particles.push(particle, &self.config);
+        }
+        self.limit_particles();
+    }
+
+    /// Release a flock that steers by separation, alignment and cohesion, and toward the pointer if one is set
+    pub fn create_particles_boids(&mut self, x: f64, y: f64, count: usize, size: f64) {
+        let count = count.min(MAX_SPAWN_COUNT);
+        self.record(InputEvent::Particles { mode: ParticleMode::Boids, x, y, count, size });
+        let color = self.random_color();
+        for _ in 0..count {
+            let offset_x = (self.rng.next_f64() - 0.5) * 60.0;
+            let offset_y = (self.rng.next_f64() - 0.5) * 60.0;
+            let heading = self.rng.next_f64() * PI * 2.0;
+            let speed = self.config.boid_max_speed * (self.rng.next_f64() * 0.4 + 0.6);
+            let vx = heading.cos() * speed;
+            let vy = heading.sin() * speed;
+            let particle = Particle::new(x + offset_x, y + offset_y, vx, vy, color, size * 0.5, ParticleMode::Boids);
+            self.particles.push(particle, &self.config);
+        }
+        self.limit_particles();
+    }
+
+    /// Burst every fireworks particle whose charge went off this step
+    fn detonate_fireworks(&mut self) {
+        let mut detonated = false;
+        // Walk backwards: removing a parent and appending its children never
+        // moves a particle that hasn't been checked yet
+        for i in (0..self.particles.len()).rev() {
+            if !self.particles.is_charge_ready(i) {
+                continue;
+            }
+            let parent = self.particles.get(i);
+            self.particles.swap_remove(i);
+            match parent.charge {
+                Charge::Shell { sparks } => self.burst_shell(&parent, sparks as usize),
+                Charge::Crackle { .. } => self.crackle(&parent),
+                Charge::None => {}
+            }
+            detonated = true;
+        }
+        if detonated {
+            self.limit_particles();
+        }
+    }
+
+    /// Ring of sparks from a shell at its apex, some of them armed to crackle
+    fn burst_shell(&mut self, shell: &Particle, count: usize) {
+        // Shells restored from a snapshot carry whatever count they were saved with
+        let count = count.min(MAX_SPAWN_COUNT);
+        let color = self.random_color();
+        for i in 0..count {
+            let angle = (PI * 2.0 / count as f64) * i as f64 + self.rng.next_f64() * 0.2;
+            let speed = self.rng.next_f64() * 3.0 + 2.0;
+            let vx = shell.vx + angle.cos() * speed;
+            let vy = shell.vy + angle.sin() * speed;
+            let spark_size = shell.size * (self.rng.next_f64() * 0.6 + 0.6);
+            let spark = Particle::new(shell.x, shell.y, vx, vy, color, spark_size, ParticleMode::Fireworks);
+            let spark = if self.rng.next_f64() < 0.3 {
+                spark.with_charge(Charge::Crackle { fuse: self.rng.next_f64() * 0.1 + 0.8 })
+            } else {
+                spark
+            };
+            self.particles.push(spark, &self.config);
+        }
+    }
+
+    /// Small, short-lived pop of embers where a spark crackles
+    fn crackle(&mut self, spark: &Particle) {
+        for _ in 0..5 {
+            let angle = self.rng.next_f64() * PI * 2.0;
+            let speed = self.rng.next_f64() * 1.5 + 0.5;
+            let vx = spark.vx + angle.cos() * speed;
+            let vy = spark.vy + angle.sin() * speed;
+            let mut ember = Particle::new(spark.x, spark.y, vx, vy, spark.color, spark.size * 0.6, ParticleMode::Fireworks);
+            ember.life = spark.life * 0.5;
+            self.particles.push(ember, &self.config);
+        }
+    }
+
+    pub fn create_lightning(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.record(InputEvent::Lightning { x, y, width, height });
+        // Generate random endpoint for lightning bolt
+        let end_x = x + (self.rng.next_f64() - 0.5) * 300.0;
+        let end_y = y + (self.rng.next_f64() * 0.6 + 0.2) * height * 0.5; // Prefer downward bolts
+
+        let color = if self.rng.next_f64() < 0.5 { Rgba::WHITE } else { Rgba::rgb(0, 255, 255) };
+        let lightning = Lightning::new(&mut self.rng, &self.config, x, y, end_x, end_y, color);
+        self.lightnings.push(lightning);
+        self.limit_lightnings();
+    }
+
+    fn limit_lightnings(&mut self) {
+        if self.lightnings.len() > self.max_lightnings {
+            let excess = self.lightnings.len() - self.max_lightnings;
+            self.lightnings.drain(0..excess);
+        }
+    }
+
+    fn limit_particles(&mut self) {
+        self.budget.enforce(&mut self.particles, &mut self.rng);
+    }
+
+    /// Advance exactly one fixed step on a `width` x `height` canvas
+    pub fn update(&mut self, width: f64, height: f64) {
+        self.record(InputEvent::Update { width, height });
+        self.width = width;
+        self.height = height;
+        self.step();
+    }
+
+    /// Advance the simulation by `dt_seconds` of wall-clock time
+    ///
+    /// Time is consumed in `FIXED_TIMESTEP` steps so the animation runs at the
+    /// same speed at any frame rate; the remainder carries over to the next
+    /// call and is reported by `alpha`. Returns the number of steps taken.
+    pub fn update_dt(&mut self, dt_seconds: f64) -> u32 {
+        self.record(InputEvent::UpdateDt { dt_seconds });
+        // Ignore bogus timestamps (clock jumps, first frame)
+        if !dt_seconds.is_finite() || dt_seconds <= 0.0 {
+            return 0;
+        }
+
+        self.accumulator += dt_seconds.min(MAX_FRAME_TIME);
+        let mut steps = 0;
+        while self.accumulator + STEP_EPSILON >= FIXED_TIMESTEP {
+            self.step();
+            self.accumulator = (self.accumulator - FIXED_TIMESTEP).max(0.0);
+            steps += 1;
+        }
+        steps
+    }
+
+    /// Fraction of a step left unsimulated by `update_dt`, in [0, 1)
+    ///
+    /// Renderers can draw particles at `prev + (current - prev) * alpha`.
+    pub fn alpha(&self) -> f64 {
+        self.accumulator / FIXED_TIMESTEP
+    }
+
+    /// Set the canvas size used by `update_dt` and mode-based creation
+    pub fn set_bounds(&mut self, width: f64, height: f64) {
+        self.record(InputEvent::SetBounds { width, height });
+        self.width = width;
+        self.height = height;
+    }
+
+    /// Canvas size as (width, height), from the last `set_bounds` or `update`
+    pub fn bounds(&self) -> (f64, f64) {
+        (self.width, self.height)
+    }
+
+    /// Draw boids toward (x, y) until `clear_pointer` is called
+    pub fn set_pointer(&mut self, x: f64, y: f64) {
+        self.record(InputEvent::SetPointer { x, y });
+        self.pointer = Some(Point { x, y });
+    }
+
+    /// Stop drawing boids toward the pointer
+    pub fn clear_pointer(&mut self) {
+        self.record(InputEvent::ClearPointer);
+        self.pointer = None;
+    }
+
+    pub fn pointer(&self) -> Option<Point> {
+        self.pointer
+    }
+
+    fn step(&mut self) {
+        let (width, height) = (self.width, self.height);
+
+        let load_factor = self.budget.decay_multiplier(self.scene_load());
+
+        // Update vines, moving finished ones to the fading list and faded
+        // ones to the pool
+        self.grown_vines.extend(self.vines.extract_if(.., |v| !v.update(width, height)));
+        for vine in self.grown_vines.extract_if(.., |v| !v.fade()) {
+            if self.vine_pool.len() < VINE_POOL_SIZE {
+                self.vine_pool.push(vine);
+            }
+        }
+
+        // Update particles
+        let ctx = StepContext {
+            width,
+            height,
+            load_factor,
+            field_time: noise::field_time(self.steps, self.seed),
+            pointer: self.pointer,
+        };
+        self.steps += 1;
+        self.particles.update(&self.config, &ctx);
+        self.detonate_fireworks();
+        self.particles.reindex(width, height);
+        self.relink();
+
+        // Update lightning
+        let config = &self.config;
+        self.lightnings.retain_mut(|l| l.update(config));
+    }
+
+    pub fn clear(&mut self) {
+        self.record(InputEvent::Clear);
+        self.vines.clear();
+        self.grown_vines.clear();
+        self.particles.clear();
+        self.links.clear();
+        self.lightnings.clear();
+        self.accumulator = 0.0;
+    }
+
+    /// Replace the physics parameters; takes effect on the next step
+    pub fn set_config(&mut self, config: EngineConfig) {
+        self.config = config.sanitized();
+        self.particles.retune(&self.config);
+        if self.is_recording() {
+            self.record(InputEvent::SetConfig(self.config.clone()));
+        }
+    }
+
+    pub fn config(&self) -> &EngineConfig {
+        &self.config
+    }
+
+    /// Swap the palette new elements are colored from; existing elements keep their color
+    pub fn set_palette(&mut self, palette: Palette) {
+        if self.is_recording() {
+            self.record(InputEvent::SetPalette(palette.clone()));
+        }
+        self.palette = palette;
+        self.particles.compact_colors();
+    }
+
+    pub fn palette(&self) -> &Palette {
+        &self.palette
+    }
+
+    /// Restart the random sequence from `seed` without clearing the scene
+    pub fn set_seed(&mut self, seed: u64) {
+        self.record(InputEvent::SetSeed(seed));
+        self.seed = seed;
+        self.rng = Rng::new(seed);
+    }
+
+    /// Seed the engine was created with (or last reseeded with)
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Choose how the particle count is kept in check; capped policies apply immediately
+    pub fn set_budget_policy(&mut self, policy: BudgetPolicy) {
+        self.record(InputEvent::SetBudgetPolicy(policy));
+        self.budget = policy;
+        self.limit_particles();
+    }
+
+    pub fn budget_policy(&self) -> BudgetPolicy {
+        self.budget
+    }
+
+    /// Hard cap on live particles; the oldest are dropped first
+    pub fn set_max_particles(&mut self, max: usize) {
+        self.set_budget_policy(BudgetPolicy::HardCap { max });
+    }
+
+    /// Total on-screen elements: particles plus every vine and lightning point
+    ///
+    /// This is the load `BudgetPolicy::DynamicDecay` scales decay by.
+    pub fn scene_load(&self) -> usize {
+        let vine_points: usize = self.vines.iter().chain(&self.grown_vines).map(|v| v.points().len()).sum();
+        let lightning_points: usize = self
+            .lightnings
+            .iter()
+            .map(|l| l.segments.len() + l.branches.iter().map(Vec::len).sum::<usize>())
+            .sum();
+        self.particles.len() + vine_points + lightning_points
+    }
+
+    /// Indices into `particles()` of every particle within `radius` of (x, y), ascending
+    ///
+    /// Use `ParticleStore::query_radius` to reuse an output buffer instead.
+    pub fn query_radius(&self, x: f64, y: f64, radius: f64) -> Vec<usize> {
+        let mut found = Vec::new();
+        self.particles.query_radius(x, y, radius, &mut found);
+        found
+    }
+
+    /// Indices into `particles()` of every particle inside the rectangle, ascending
+    pub fn query_rect(&self, min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> Vec<usize> {
+        let mut found = Vec::new();
+        self.particles.query_rect(min_x, min_y, max_x, max_y, &mut found);
+        found
+    }
+
+    // FFI-friendly getter methods
+    pub fn particles(&self) -> &ParticleStore {
+        &self.particles
+    }
+
+    pub fn particles_len(&self) -> usize {
+        self.particles.len()
+    }
+
+    pub fn vines_slice(&self) -> &[Vine] {
+        &self.vines
+    }
+
+    pub fn grown_vines_slice(&self) -> &[Vine] {
+        &self.grown_vines
+    }
+
+    pub fn lightnings_slice(&self) -> &[Lightning] {
+        &self.lightnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grown_vines_fade_out() {
+        let mut bloom = DigitalBloom::new();
+        bloom.create_vine(200.0, 200.0, 4.0);
+        for _ in 0..1000 {
+            bloom.update(400.0, 400.0);
+        }
+        assert!(bloom.vines_slice().is_empty());
+        assert!(bloom.grown_vines_slice().is_empty());
+    }
+
+    #[test]
+    fn test_same_seed_is_deterministic() {
+        let mut a = DigitalBloom::with_seed(7);
+        let mut b = DigitalBloom::with_seed(7);
+        for bloom in [&mut a, &mut b] {
+            bloom.create_vine(200.0, 200.0, 4.0);
+            bloom.create_particles_burst(100.0, 100.0, 12, 4.0);
+            bloom.create_lightning(150.0, 50.0, 400.0, 400.0);
+            for _ in 0..30 {
+                bloom.update(400.0, 400.0);
+            }
+        }
+        let positions = |bloom: &DigitalBloom| -> Vec<(f64, f64)> {
+            bloom.particles().iter().map(|p| (p.x(), p.y())).collect()
+        };
+        assert_eq!(positions(&a), positions(&b));
+        assert_eq!(a.vines_slice()[0].points(), b.vines_slice()[0].points());
+        assert_eq!(a.lightnings_slice()[0].segments, b.lightnings_slice()[0].segments);
+    }
+
+    #[test]
+    fn test_update_dt_uses_fixed_steps() {
+        let mut bloom = DigitalBloom::with_seed(1);
+        bloom.set_bounds(400.0, 400.0);
+        assert_eq!(bloom.update_dt(FIXED_TIMESTEP * 2.5), 2);
+        assert!((bloom.alpha() - 0.5).abs() < 1e-9);
+        assert_eq!(bloom.update_dt(FIXED_TIMESTEP * 0.5), 1);
+        assert_eq!(bloom.update_dt(10.0), (MAX_FRAME_TIME / FIXED_TIMESTEP) as u32);
+        assert_eq!(bloom.update_dt(f64::NAN), 0);
+    }
+
+    #[test]
+    fn test_frame_rate_independent() {
+        let mut at_60 = DigitalBloom::with_seed(3);
+        let mut at_120 = DigitalBloom::with_seed(3);
+        for bloom in [&mut at_60, &mut at_120] {
+            bloom.set_bounds(400.0, 400.0);
+            bloom.create_particles_gravity(200.0, 200.0, 5, 4.0);
+        }
+        for _ in 0..60 {
+            at_60.update_dt(1.0 / 60.0);
+            at_120.update_dt(1.0 / 120.0);
+            at_120.update_dt(1.0 / 120.0);
+        }
+        let a = at_60.particles().get(0);
+        let b = at_120.particles().get(0);
+        assert!((a.y() - b.y()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_config_changes_physics() {
+        let mut bloom = DigitalBloom::with_seed(5);
+        bloom.set_config(EngineConfig { gravity: 0.0, friction: 1.0, ..EngineConfig::default() });
+        bloom.create_particles_bounce(200.0, 200.0, 1, 4.0);
+        let (vx, vy) = {
+            let p = bloom.particles().get(0);
+            (p.vx, p.vy)
+        };
+        bloom.update(400.0, 400.0);
+        let p = bloom.particles().get(0);
+        assert_eq!((p.vx, p.vy), (vx, vy));
+    }
+
+    #[test]
+    fn test_config_sanitizes_segments() {
+        let mut bloom = DigitalBloom::with_seed(5);
+        bloom.set_config(EngineConfig { lightning_segments: 0, ..EngineConfig::default() });
+        bloom.create_lightning(100.0, 100.0, 400.0, 400.0);
+        assert_eq!(bloom.lightnings_slice()[0].segments.len(), 2);
+    }
+
+    #[test]
+    fn test_max_particles_drops_oldest() {
+        let mut bloom = DigitalBloom::new();
+        bloom.set_max_particles(10);
+        bloom.create_particles_burst(100.0, 100.0, 25, 4.0);
+        assert_eq!(bloom.particles_len(), 10);
+    }
+
+    #[test]
+    fn test_scene_load_counts_every_element() {
+        let mut bloom = DigitalBloom::with_seed(2);
+        bloom.create_particles_burst(100.0, 100.0, 5, 4.0);
+        bloom.create_vine(200.0, 200.0, 4.0);
+        bloom.create_lightning(150.0, 50.0, 400.0, 400.0);
+        let lightning = &bloom.lightnings_slice()[0];
+        let lightning_points = lightning.segments.len() + lightning.branches.iter().map(Vec::len).sum::<usize>();
+        assert_eq!(bloom.scene_load(), 5 + 1 + lightning_points);
+    }
+
+    #[test]
+    fn test_dynamic_decay_shortens_life_under_load() {
+        let mut calm = DigitalBloom::with_seed(4);
+        let mut busy = DigitalBloom::with_seed(4);
+        for bloom in [&mut calm, &mut busy] {
+            bloom.set_budget_policy(BudgetPolicy::DynamicDecay);
+            bloom.create_particles_constellation(200.0, 200.0, 1, 4.0);
+        }
+        busy.create_particles_constellation(200.0, 200.0, 900, 4.0);
+        calm.update(400.0, 400.0);
+        busy.update(400.0, 400.0);
+        assert!(busy.particles().get(0).life() < calm.particles().get(0).life());
+    }
+
+    #[test]
+    fn test_fireworks_burst_at_apex_then_crackle() {
+        let mut bloom = DigitalBloom::with_seed(6);
+        bloom.set_budget_policy(BudgetPolicy::None);
+        bloom.create(ParticleMode::Fireworks, 200.0, 380.0, 30, 4.0);
+        assert_eq!(bloom.particles_len(), 1);
+
+        let mut climb = 0;
+        while bloom.particles_len() == 1 {
+            assert!(bloom.particles().get(0).vy < 0.0);
+            bloom.update(400.0, 400.0);
+            climb += 1;
+        }
+        assert!(climb > 10);
+        assert_eq!(bloom.particles_len(), 30);
+
+        // Crackling sparks are replaced by several embers, so the count rises at some point
+        let mut crackled = false;
+        for _ in 0..120 {
+            let before = bloom.particles_len();
+            bloom.update(400.0, 400.0);
+            crackled |= bloom.particles_len() > before;
+        }
+        assert!(crackled);
+    }
+
+    #[test]
+    fn test_fireworks_respect_budget() {
+        let mut bloom = DigitalBloom::with_seed(6);
+        bloom.set_max_particles(10);
+        bloom.create_particles_fireworks(200.0, 380.0, 30, 4.0);
+        for _ in 0..60 {
+            bloom.update(400.0, 400.0);
+            assert!(bloom.particles_len() <= 10);
+        }
+    }
+
+    #[test]
+    fn test_smoke_rises_swirls_and_grows() {
+        let drift = |seed: u64| -> Vec<(f64, f64, f64)> {
+            let mut bloom = DigitalBloom::with_seed(seed);
+            bloom.create(ParticleMode::Smoke, 200.0, 300.0, 10, 4.0);
+            for _ in 0..90 {
+                bloom.update(400.0, 400.0);
+            }
+            bloom.particles().iter().map(|p| (p.x(), p.y(), p.size())).collect()
+        };
+        let puffs = drift(8);
+        assert_eq!(puffs.len(), 10);
+        // The field may carry a puff down, but on average smoke rises
+        let mean_y = puffs.iter().map(|p| p.1).sum::<f64>() / puffs.len() as f64;
+        assert!(mean_y < 280.0);
+        assert!(puffs.iter().all(|p| p.2 > 4.0));
+        // Each seed starts the field somewhere else
+        assert_ne!(puffs, drift(9));
+    }
+
+    #[test]
+    fn test_queries_match_a_full_scan() {
+        let mut bloom = DigitalBloom::with_seed(12);
+        bloom.create_particles_burst(120.0, 140.0, 60, 4.0);
+        bloom.create_particles_constellation(300.0, 260.0, 40, 4.0);
+        bloom.create_particles_boids(200.0, 200.0, 30, 4.0);
+        let scan = |bloom: &DigitalBloom, x: f64, y: f64, r: f64| -> Vec<usize> {
+            (0..bloom.particles_len())
+                .filter(|&i| {
+                    let p = bloom.particles().get(i);
+                    (p.x() - x).hypot(p.y() - y) <= r
+                })
+                .collect()
+        };
+
+        // Before the first step the grid is stale and queries scan
+        assert_eq!(bloom.query_radius(120.0, 140.0, 30.0), scan(&bloom, 120.0, 140.0, 30.0));
+        for _ in 0..10 {
+            bloom.update(400.0, 400.0);
+        }
+        for (x, y, r) in [(120.0, 140.0, 30.0), (300.0, 260.0, 75.0), (0.0, 0.0, 600.0), (200.0, 200.0, 0.0)] {
+            assert_eq!(bloom.query_radius(x, y, r), scan(&bloom, x, y, r));
+        }
+        let rect = bloom.query_rect(150.0, 150.0, 250.0, 250.0);
+        assert!(!rect.is_empty());
+        assert!(rect.iter().all(|&i| {
+            let p = bloom.particles().get(i);
+            (150.0..=250.0).contains(&p.x()) && (150.0..=250.0).contains(&p.y())
+        }));
+        assert_eq!(rect.len(), bloom.particles().iter().filter(|p| (150.0..=250.0).contains(&p.x()) && (150.0..=250.0).contains(&p.y())).count());
+    }
+
+    #[test]
+    fn test_boids_flock_together_and_follow_pointer() {
+        let spread = |bloom: &DigitalBloom| -> (f64, f64, f64) {
+            let n = bloom.particles_len() as f64;
+            let (cx, cy) = bloom.particles().iter().fold((0.0, 0.0), |(x, y), p| (x + p.x() / n, y + p.y() / n));
+            let heading = bloom.particles().iter().fold((0.0, 0.0), |(x, y), p| (x + p.vx / n, y + p.vy / n));
+            // Mean speed of the flock as a whole: near zero when headings are random
+            (cx, cy, heading.0.hypot(heading.1))
+        };
+
+        let mut bloom = DigitalBloom::with_seed(11);
+        bloom.create_particles_boids(200.0, 200.0, 30, 4.0);
+        let (_, _, scattered) = spread(&bloom);
+        for _ in 0..120 {
+            bloom.update(400.0, 400.0);
+        }
+        assert_eq!(bloom.particles_len(), 30);
+        let (_, _, aligned) = spread(&bloom);
+        assert!(aligned > scattered && aligned > bloom.config().boid_max_speed * 0.5, "{scattered} -> {aligned}");
+
+        bloom.set_pointer(340.0, 60.0);
+        for _ in 0..240 {
+            bloom.update(400.0, 400.0);
+        }
+        let (cx, cy, _) = spread(&bloom);
+        assert!((cx - 340.0).hypot(cy - 60.0) < 80.0, "flock centre ({cx}, {cy})");
+        bloom.clear_pointer();
+        assert_eq!(bloom.pointer(), None);
+    }
+}
@@ -0,0 +1,201 @@
+//! Strategies for keeping a busy scene within what the renderer can draw
+//!
+//! Capped policies are enforced whenever particles are added. Dynamic decay
+//! never removes anything directly; it shortens particle lifetimes as the scene
+//! load (particles plus every vine and lightning point) rises.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::particle::ParticleStore;
+use crate::rng::Rng;
+
+/// How an engine limits its particle count
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(tag = "type", rename_all = "snake_case"))]
+pub enum BudgetPolicy {
+    /// No limit at all
+    None,
+    /// Keep at most `max` particles, dropping the oldest first
+    HardCap { max: usize },
+    /// No hard limit; decay speeds up as the scene load rises
+    DynamicDecay,
+    /// Keep at most `max` particles, dropping random ones
+    RandomCull { max: usize },
+    /// Keep at most `max` particles, dropping the smallest (as drawn) first
+    SmallestFirst { max: usize },
+}
+
+impl Default for BudgetPolicy {
+    fn default() -> Self {
+        BudgetPolicy::HardCap { max: 500 }
+    }
+}
+
+impl BudgetPolicy {
+    /// Build a policy from its compact `(kind, max)` encoding
+    ///
+    /// Kinds are 0 = None, 1 = HardCap, 2 = DynamicDecay, 3 = RandomCull,
+    /// 4 = SmallestFirst; `max` is ignored by uncapped policies.
+    pub fn from_raw(kind: u8, max: usize) -> Option<Self> {
+        match kind {
+            0 => Some(BudgetPolicy::None),
+            1 => Some(BudgetPolicy::HardCap { max }),
+            2 => Some(BudgetPolicy::DynamicDecay),
+            3 => Some(BudgetPolicy::RandomCull { max }),
+            4 => Some(BudgetPolicy::SmallestFirst { max }),
+            _ => None,
+        }
+    }
+
+    /// Compact `(kind, max)` encoding used by snapshots and the C API
+    pub fn to_raw(self) -> (u8, usize) {
+        match self {
+            BudgetPolicy::None => (0, 0),
+            BudgetPolicy::HardCap { max } => (1, max),
+            BudgetPolicy::DynamicDecay => (2, 0),
+            BudgetPolicy::RandomCull { max } => (3, max),
+            BudgetPolicy::SmallestFirst { max } => (4, max),
+        }
+    }
+
+    /// Particle limit, if this policy has one
+    pub fn max_particles(self) -> Option<usize> {
+        match self {
+            BudgetPolicy::HardCap { max } | BudgetPolicy::RandomCull { max } | BudgetPolicy::SmallestFirst { max } => Some(max),
+            BudgetPolicy::None | BudgetPolicy::DynamicDecay => None,
+        }
+    }
+
+    /// Decay multiplier for a scene with `load` on-screen elements
+    pub(crate) fn decay_multiplier(self, load: usize) -> f64 {
+        match self {
+            BudgetPolicy::DynamicDecay => load_factor(load),
+            _ => 1.0,
+        }
+    }
+
+    /// Drop particles until the policy's limit is met
+    pub(crate) fn enforce(self, particles: &mut ParticleStore, rng: &mut Rng) {
+        let Some(max) = self.max_particles() else {
+            return;
+        };
+        if particles.len() <= max {
+            return;
+        }
+        let excess = particles.len() - max;
+        if excess == particles.len() {
+            particles.clear();
+            return;
+        }
+
+        let mut indices = particles.take_scratch();
+        indices.clear();
+        indices.extend(0..particles.len());
+        match self {
+            BudgetPolicy::RandomCull { .. } => {
+                // Partial Fisher-Yates over indices picks `excess` distinct victims
+                for i in 0..excess {
+                    let j = i + (rng.next_f64() * (indices.len() - i) as f64) as usize;
+                    indices.swap(i, j);
+                }
+            }
+            BudgetPolicy::SmallestFirst { .. } => {
+                let (sizes, lives, born) = (particles.sizes(), particles.lives(), particles.born());
+                // Ties in drawn size drop the oldest first
+                indices.sort_unstable_by(|&a, &b| {
+                    (sizes[a] * lives[a]).total_cmp(&(sizes[b] * lives[b])).then(born[a].cmp(&born[b]))
+                });
+            }
+            _ => {
+                // Storage order isn't spawn order, so find the `excess` oldest by sequence number
+                let born = particles.born();
+                indices.select_nth_unstable_by_key(excess, |&i| born[i]);
+            }
+        }
+        particles.remove_indices(&mut indices[..excess]);
+        particles.put_scratch(indices);
+    }
+}
+
+/// Decay multiplier for a scene containing `total` elements
+fn load_factor(total: usize) -> f64 {
+    if total < 200 {
+        1.0 // Normal decay
+    } else if total < 400 {
+        1.0 + (total - 200) as f64 * 0.0025 // 1.0 → 1.5
+    } else if total < 800 {
+        1.5 + (total - 400) as f64 * 0.0025 // 1.5 → 2.5
+    } else {
+        2.5 + ((total - 800) as f64 * 0.00375).min(1.5) // 2.5 → 4.0 (cap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Rgba;
+    use crate::config::EngineConfig;
+    use crate::mode::ParticleMode;
+    use crate::particle::Particle;
+
+    fn particles(sizes: &[f64]) -> ParticleStore {
+        let config = EngineConfig::default();
+        let mut store = ParticleStore::default();
+        for &size in sizes {
+            store.push(Particle::new(0.0, 0.0, 0.0, 0.0, Rgba::WHITE, size, ParticleMode::Burst), &config);
+        }
+        store
+    }
+
+    /// Surviving sizes, sorted since removal reorders the store
+    fn sizes(particles: &ParticleStore) -> Vec<f64> {
+        let mut sizes = particles.sizes().to_vec();
+        sizes.sort_by(f64::total_cmp);
+        sizes
+    }
+
+    #[test]
+    fn test_load_factor_curve() {
+        assert_eq!(load_factor(0), 1.0);
+        assert!((load_factor(400) - 1.5).abs() < 1e-9);
+        assert!((load_factor(800) - 2.5).abs() < 1e-9);
+        assert_eq!(load_factor(10_000), 4.0);
+    }
+
+    #[test]
+    fn test_capped_policies() {
+        let mut rng = Rng::new(1);
+
+        let mut list = particles(&[5.0, 1.0, 4.0, 2.0, 3.0]);
+        BudgetPolicy::HardCap { max: 3 }.enforce(&mut list, &mut rng);
+        assert_eq!(sizes(&list), [2.0, 3.0, 4.0]);
+
+        let mut list = particles(&[5.0, 1.0, 4.0, 2.0, 3.0]);
+        BudgetPolicy::SmallestFirst { max: 3 }.enforce(&mut list, &mut rng);
+        assert_eq!(sizes(&list), [3.0, 4.0, 5.0]);
+
+        let mut list = particles(&[5.0, 1.0, 4.0, 2.0, 3.0]);
+        BudgetPolicy::RandomCull { max: 3 }.enforce(&mut list, &mut rng);
+        assert_eq!(list.len(), 3);
+
+        let mut list = particles(&[5.0, 1.0, 4.0, 2.0, 3.0]);
+        BudgetPolicy::DynamicDecay.enforce(&mut list, &mut rng);
+        assert_eq!(list.len(), 5);
+    }
+
+    #[test]
+    fn test_raw_round_trip() {
+        for policy in [
+            BudgetPolicy::None,
+            BudgetPolicy::HardCap { max: 10 },
+            BudgetPolicy::DynamicDecay,
+            BudgetPolicy::RandomCull { max: 20 },
+            BudgetPolicy::SmallestFirst { max: 30 },
+        ] {
+            let (kind, max) = policy.to_raw();
+            assert_eq!(BudgetPolicy::from_raw(kind, max), Some(policy));
+        }
+        assert_eq!(BudgetPolicy::from_raw(5, 0), None);
+    }
+}
@@ -0,0 +1,86 @@
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// 8-bit RGBA color
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rgba {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Rgba {
+    pub const WHITE: Rgba = Rgba::rgb(255, 255, 255);
+    /// Dark backdrop the web app draws on, the default for renders and SVGs
+    pub const BACKDROP: Rgba = Rgba::rgb(26, 26, 36);
+
+    pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Rgba { r, g, b, a }
+    }
+
+    /// Opaque color
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Rgba { r, g, b, a: 255 }
+    }
+
+    /// Parse "#rrggbb" or "#rrggbbaa" (the leading '#' is optional)
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.trim_start_matches('#');
+        if !hex.is_ascii() || (hex.len() != 6 && hex.len() != 8) {
+            return None;
+        }
+
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        let a = if hex.len() == 8 { channel(6)? } else { 255 };
+        Some(Rgba::new(channel(0)?, channel(2)?, channel(4)?, a))
+    }
+
+    /// Format as "#rrggbb", or "#rrggbbaa" when not fully opaque
+    pub fn to_hex(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for Rgba {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)?;
+        if self.a != 255 {
+            write!(f, "{:02x}", self.a)?;
+        }
+        Ok(())
+    }
+}
+
+// Colors cross the JS boundary as hex strings
+#[cfg(feature = "serde")]
+impl Serialize for Rgba {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Rgba {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        Rgba::from_hex(&hex).ok_or_else(|| serde::de::Error::custom(format!("invalid color {hex:?}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_round_trip() {
+        assert_eq!(Rgba::from_hex("#ff00ff"), Some(Rgba::rgb(255, 0, 255)));
+        assert_eq!(Rgba::from_hex("00ffff80"), Some(Rgba::new(0, 255, 255, 128)));
+        assert_eq!(Rgba::from_hex("#ff00"), None);
+        assert_eq!(Rgba::from_hex("#gg0000"), None);
+        assert_eq!(Rgba::rgb(255, 105, 180).to_hex(), "#ff69b4");
+        assert_eq!(Rgba::new(1, 2, 3, 4).to_hex(), "#01020304");
+    }
+}
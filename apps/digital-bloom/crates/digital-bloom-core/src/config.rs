@@ -0,0 +1,121 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::mode::ParticleMode;
+
+/// Tunable physics parameters
+///
+/// All rates are per fixed step (1/60s). The defaults reproduce the original
+/// hand-tuned behaviour.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct EngineConfig {
+    /// Velocity multiplier applied every step (1.0 = no drag)
+    pub friction: f64,
+    /// Downward acceleration for gravity and fireworks particles
+    pub gravity: f64,
+    /// Fraction of velocity kept when a bounce particle hits a wall
+    pub bounce_restitution: f64,
+    /// Life lost per step by gravity, bounce, burst and fireworks particles
+    pub particle_decay: f64,
+    /// Life lost per step by constellation stars
+    pub constellation_decay: f64,
+    /// Life lost per step by vortex particles
+    pub vortex_decay: f64,
+    /// Life lost per step by lightning bolts
+    pub lightning_decay: f64,
+    /// Radians a vortex particle orbits per step
+    pub vortex_angular_velocity: f64,
+    /// Pixels a vortex particle spirals inward per step
+    pub vortex_spiral_speed: f64,
+    /// Number of segments in the main lightning bolt
+    pub lightning_segments: u32,
+    /// Maximum perpendicular offset of a main bolt point, in pixels
+    pub lightning_jitter: f64,
+    /// Maximum offset of a branch point, in pixels
+    pub lightning_branch_jitter: f64,
+    /// Longest random vine length (added to `vine_min_length`), in points
+    pub vine_max_length: f64,
+    /// Shortest vine length, in points
+    pub vine_min_length: f64,
+    /// Life lost per step by smoke puffs
+    pub smoke_decay: f64,
+    /// Pixels a smoke puff's size grows per step
+    pub smoke_growth: f64,
+    /// Speed of the curl-noise field smoke drifts through, in pixels per step
+    pub smoke_curl_strength: f64,
+    /// Life lost per step by boids
+    pub boid_decay: f64,
+    /// How far a boid sees its flockmates, in pixels
+    pub boid_view_radius: f64,
+    /// Strength of the push away from crowding neighbours
+    pub boid_separation: f64,
+    /// Fraction of the difference from the neighbours' mean velocity matched per step
+    pub boid_alignment: f64,
+    /// Fraction of the distance to the neighbours' centre closed per step
+    pub boid_cohesion: f64,
+    /// Top boid speed, in pixels per step
+    pub boid_max_speed: f64,
+    /// Acceleration toward the pointer while one is set, in pixels per step²
+    pub boid_pointer_pull: f64,
+    /// Constellation stars closer than this are joined by a link, in pixels (0 = no links)
+    pub constellation_link_distance: f64,
+}
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        EngineConfig {
+            friction: 0.99,
+            gravity: 0.3,
+            bounce_restitution: 0.7,
+            particle_decay: 0.005,
+            constellation_decay: 0.002,
+            vortex_decay: 0.003,
+            lightning_decay: 0.02,
+            vortex_angular_velocity: 0.08,
+            vortex_spiral_speed: 0.5,
+            lightning_segments: 15,
+            lightning_jitter: 30.0,
+            lightning_branch_jitter: 15.0,
+            vine_max_length: 200.0,
+            vine_min_length: 50.0,
+            smoke_decay: 0.004,
+            smoke_growth: 0.05,
+            smoke_curl_strength: 1.0,
+            boid_decay: 0.0015,
+            boid_view_radius: 40.0,
+            boid_separation: 0.5,
+            boid_alignment: 0.1,
+            boid_cohesion: 0.02,
+            boid_max_speed: 3.0,
+            boid_pointer_pull: 0.1,
+            constellation_link_distance: 80.0,
+        }
+    }
+}
+
+impl EngineConfig {
+    /// Life lost per step for a particle of the given mode
+    pub fn decay_for(&self, mode: ParticleMode) -> f64 {
+        match mode {
+            ParticleMode::Constellation => self.constellation_decay,
+            ParticleMode::Vortex => self.vortex_decay,
+            ParticleMode::Lightning => self.lightning_decay,
+            ParticleMode::Smoke => self.smoke_decay,
+            ParticleMode::Boids => self.boid_decay,
+            _ => self.particle_decay,
+        }
+    }
+
+    /// Clamp values that would break the simulation (host input is untrusted)
+    pub fn sanitized(mut self) -> Self {
+        self.lightning_segments = self.lightning_segments.clamp(1, 1000);
+        self.vine_max_length = self.vine_max_length.max(0.0);
+        self.vine_min_length = self.vine_min_length.max(0.0);
+        self.boid_view_radius = self.boid_view_radius.max(0.0);
+        self.boid_max_speed = self.boid_max_speed.max(0.0);
+        self.constellation_link_distance = self.constellation_link_distance.max(0.0);
+        self
+    }
+}
@@ -0,0 +1,417 @@
+//! PNG sequence and animated GIF/APNG export
+//!
+//! Frames are rasterized with the `render` module while the simulation runs,
+//! either live from an engine or by replaying a `Recording`. One simulation
+//! step is 1/60s, so an animation plays back at the speed it was drawn.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::bloom::{DigitalBloom, FIXED_TIMESTEP};
+use crate::render::{Framebuffer, RenderOptions};
+use crate::replay::{InputEvent, Recording};
+use crate::snapshot::SnapshotError;
+
+/// Container for a single-file animation
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AnimationFormat {
+    /// Animated GIF, quantized to 256 colors per frame
+    Gif,
+    /// Animated PNG, full RGBA
+    Apng,
+}
+
+/// Size and pacing of an exported animation
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExportOptions {
+    pub width: usize,
+    pub height: usize,
+    /// Number of frames to write; for recordings, the most frames to write
+    pub frames: usize,
+    /// Simulation steps (or recorded host frames) between written frames
+    pub frame_stride: usize,
+    pub render: RenderOptions,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        ExportOptions {
+            width: 400,
+            height: 400,
+            frames: 120,
+            frame_stride: 2,
+            render: RenderOptions::default(),
+        }
+    }
+}
+
+impl ExportOptions {
+    /// Wall-clock time one written frame stands for, in seconds
+    pub fn frame_duration(&self) -> f64 {
+        self.frame_stride.max(1) as f64 * FIXED_TIMESTEP
+    }
+}
+
+#[derive(Debug)]
+pub enum ExportError {
+    Io(io::Error),
+    /// The PNG or GIF encoder rejected the frame data
+    Encoding(String),
+    /// Zero-sized frames, or larger than the format allows (65535 px for GIF)
+    InvalidSize,
+    /// An animation would have no frames
+    NoFrames,
+    /// `frame_stride` makes each frame longer than the format's delay field can hold
+    InvalidFrameDelay,
+    /// More frames than the format can declare
+    TooManyFrames,
+    /// A recording could not be replayed
+    Snapshot(SnapshotError),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Io(err) => write!(f, "export failed: {err}"),
+            ExportError::Encoding(err) => write!(f, "could not encode frame: {err}"),
+            ExportError::InvalidSize => write!(f, "unsupported frame size"),
+            ExportError::NoFrames => write!(f, "animation has no frames"),
+            ExportError::InvalidFrameDelay => write!(f, "frame delay too long for the format"),
+            ExportError::TooManyFrames => write!(f, "too many frames for the format"),
+            ExportError::Snapshot(err) => write!(f, "could not replay recording: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl From<io::Error> for ExportError {
+    fn from(err: io::Error) -> Self {
+        ExportError::Io(err)
+    }
+}
+
+impl From<png::EncodingError> for ExportError {
+    fn from(err: png::EncodingError) -> Self {
+        match err {
+            png::EncodingError::IoError(err) => ExportError::Io(err),
+            other => ExportError::Encoding(other.to_string()),
+        }
+    }
+}
+
+impl From<gif::EncodingError> for ExportError {
+    fn from(err: gif::EncodingError) -> Self {
+        match err {
+            gif::EncodingError::Io(err) => ExportError::Io(err),
+            other => ExportError::Encoding(other.to_string()),
+        }
+    }
+}
+
+impl From<SnapshotError> for ExportError {
+    fn from(err: SnapshotError) -> Self {
+        ExportError::Snapshot(err)
+    }
+}
+
+impl DigitalBloom {
+    /// Run the simulation and write `frame_00000.png`, `frame_00001.png`, ... into `dir`
+    ///
+    /// Returns the paths written, in order.
+    pub fn export_png_sequence(&mut self, dir: &Path, options: &ExportOptions) -> Result<Vec<PathBuf>, ExportError> {
+        let mut sink = PngSequence::new(dir, options)?;
+        self.run_frames(&mut sink, options)?;
+        Ok(sink.paths)
+    }
+
+    /// Run the simulation and write it as a single animated GIF or APNG
+    pub fn export_animation<W: Write>(&mut self, format: AnimationFormat, writer: W, options: &ExportOptions) -> Result<(), ExportError> {
+        let mut sink = AnimationSink::new(format, writer, options, options.frames)?;
+        self.run_frames(&mut sink, options)?;
+        sink.finish()
+    }
+
+    fn run_frames(&mut self, sink: &mut dyn FrameSink, options: &ExportOptions) -> Result<(), ExportError> {
+        let mut frame = Framebuffer::new(options.width, options.height);
+        for _ in 0..options.frames {
+            self.render_into(&mut frame, &options.render);
+            sink.write_frame(&frame)?;
+            for _ in 0..options.frame_stride.max(1) {
+                self.update(self.width, self.height);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Recording {
+    /// Replay the recording into `frame_00000.png`, `frame_00001.png`, ... in `dir`
+    ///
+    /// A frame is written after every `frame_stride` recorded `update`/`update_dt`
+    /// calls, so the animation follows the host's original frame timing.
+    pub fn export_png_sequence(&self, dir: &Path, options: &ExportOptions) -> Result<Vec<PathBuf>, ExportError> {
+        let mut sink = PngSequence::new(dir, options)?;
+        self.replay_frames(&mut sink, options)?;
+        Ok(sink.paths)
+    }
+
+    /// Replay the recording into a single animated GIF or APNG
+    pub fn export_animation<W: Write>(&self, format: AnimationFormat, writer: W, options: &ExportOptions) -> Result<(), ExportError> {
+        let mut sink = AnimationSink::new(format, writer, options, self.frame_count(options))?;
+        self.replay_frames(&mut sink, options)?;
+        sink.finish()
+    }
+
+    fn is_frame(event: &InputEvent) -> bool {
+        matches!(event, InputEvent::Update { .. } | InputEvent::UpdateDt { .. })
+    }
+
+    fn frame_count(&self, options: &ExportOptions) -> usize {
+        let host_frames = self.events().iter().filter(|e| Recording::is_frame(e)).count();
+        (host_frames / options.frame_stride.max(1)).min(options.frames)
+    }
+
+    fn replay_frames(&self, sink: &mut dyn FrameSink, options: &ExportOptions) -> Result<(), ExportError> {
+        let total = self.frame_count(options);
+        let mut frame = Framebuffer::new(options.width, options.height);
+        let mut bloom = self.start()?;
+        let mut host_frames = 0;
+        let mut written = 0;
+
+        for event in self.events() {
+            if written == total {
+                break;
+            }
+            bloom.apply(event)?;
+            if Recording::is_frame(event) {
+                host_frames += 1;
+                if host_frames % options.frame_stride.max(1) == 0 {
+                    bloom.render_into(&mut frame, &options.render);
+                    sink.write_frame(&frame)?;
+                    written += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Framebuffer {
+    /// Encode the frame as a single RGBA PNG
+    pub fn write_png<W: Write>(&self, writer: W) -> Result<(), ExportError> {
+        let (width, height) = check_size(self.width(), self.height())?;
+        let mut writer = png_encoder(writer, width, height).write_header()?;
+        writer.write_image_data(self.pixels())?;
+        Ok(writer.finish()?)
+    }
+}
+
+// ==================== FRAME SINKS ====================
+
+trait FrameSink {
+    fn write_frame(&mut self, frame: &Framebuffer) -> Result<(), ExportError>;
+}
+
+fn check_size(width: usize, height: usize) -> Result<(u32, u32), ExportError> {
+    if width == 0 || height == 0 {
+        return Err(ExportError::InvalidSize);
+    }
+    if !Framebuffer::fits(width, height) {
+        return Err(ExportError::InvalidSize);
+    }
+    let width = u32::try_from(width).map_err(|_| ExportError::InvalidSize)?;
+    let height = u32::try_from(height).map_err(|_| ExportError::InvalidSize)?;
+    Ok((width, height))
+}
+
+fn png_encoder<W: Write>(writer: W, width: u32, height: u32) -> png::Encoder<'static, W> {
+    let mut encoder = png::Encoder::new(writer, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+}
+
+struct PngSequence {
+    dir: PathBuf,
+    paths: Vec<PathBuf>,
+}
+
+impl PngSequence {
+    fn new(dir: &Path, options: &ExportOptions) -> Result<Self, ExportError> {
+        check_size(options.width, options.height)?;
+        std::fs::create_dir_all(dir)?;
+        Ok(PngSequence { dir: dir.to_path_buf(), paths: Vec::new() })
+    }
+}
+
+impl FrameSink for PngSequence {
+    fn write_frame(&mut self, frame: &Framebuffer) -> Result<(), ExportError> {
+        let path = self.dir.join(format!("frame_{:05}.png", self.paths.len()));
+        let file = BufWriter::new(File::create(&path)?);
+        frame.write_png(file)?;
+        self.paths.push(path);
+        Ok(())
+    }
+}
+
+enum AnimationSink<W: Write> {
+    // `pixels` is reused each frame, since quantizing overwrites it
+    Gif { encoder: gif::Encoder<W>, width: u16, height: u16, delay: u16, pixels: Vec<u8> },
+    Apng { writer: png::Writer<W> },
+}
+
+impl<W: Write> AnimationSink<W> {
+    fn new(format: AnimationFormat, writer: W, options: &ExportOptions, frames: usize) -> Result<Self, ExportError> {
+        if frames == 0 {
+            return Err(ExportError::NoFrames);
+        }
+        let (width, height) = check_size(options.width, options.height)?;
+        let duration = options.frame_duration();
+        let stride = options.frame_stride.max(1);
+        match format {
+            AnimationFormat::Gif => {
+                // GIF delays are in hundredths of a second
+                let delay = (duration * 100.0).round().max(1.0);
+                if delay > u16::MAX as f64 {
+                    return Err(ExportError::InvalidFrameDelay);
+                }
+                let width = u16::try_from(width).map_err(|_| ExportError::InvalidSize)?;
+                let height = u16::try_from(height).map_err(|_| ExportError::InvalidSize)?;
+                let mut encoder = gif::Encoder::new(writer, width, height, &[])?;
+                encoder.set_repeat(gif::Repeat::Infinite)?;
+                let pixels = vec![0; options.width * options.height * 4];
+                Ok(AnimationSink::Gif { encoder, width, height, delay: delay as u16, pixels })
+            }
+            AnimationFormat::Apng => {
+                // APNG delays are a fraction, so `stride` steps of 1/60s is exact
+                let stride = u16::try_from(stride).map_err(|_| ExportError::InvalidFrameDelay)?;
+                // APNG declares its frame count up front
+                let frames = u32::try_from(frames).map_err(|_| ExportError::TooManyFrames)?;
+                let mut encoder = png_encoder(writer, width, height);
+                encoder.set_animated(frames, 0)?;
+                encoder.set_frame_delay(stride, (1.0 / FIXED_TIMESTEP).round() as u16)?;
+                Ok(AnimationSink::Apng { writer: encoder.write_header()? })
+            }
+        }
+    }
+
+    fn finish(self) -> Result<(), ExportError> {
+        match self {
+            AnimationSink::Gif { .. } => Ok(()),
+            AnimationSink::Apng { writer } => Ok(writer.finish()?),
+        }
+    }
+}
+
+impl<W: Write> FrameSink for AnimationSink<W> {
+    fn write_frame(&mut self, frame: &Framebuffer) -> Result<(), ExportError> {
+        match self {
+            AnimationSink::Gif { encoder, width, height, delay, pixels } => {
+                pixels.copy_from_slice(frame.pixels());
+                let mut gif_frame = gif::Frame::from_rgba_speed(*width, *height, pixels, 10);
+                gif_frame.delay = *delay;
+                encoder.write_frame(&gif_frame)?;
+            }
+            AnimationSink::Apng { writer } => writer.write_image_data(frame.pixels())?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mode::ParticleMode;
+
+    fn options() -> ExportOptions {
+        ExportOptions { width: 40, height: 30, frames: 4, frame_stride: 3, render: RenderOptions::default() }
+    }
+
+    fn scene() -> DigitalBloom {
+        let mut bloom = DigitalBloom::with_seed(4);
+        bloom.set_bounds(400.0, 300.0);
+        bloom.create(ParticleMode::Burst, 200.0, 150.0, 20, 8.0);
+        bloom
+    }
+
+    #[test]
+    fn test_png_sequence() {
+        let dir = std::env::temp_dir().join(format!("digital-bloom-export-{}", std::process::id()));
+        let paths = scene().export_png_sequence(&dir, &options()).unwrap();
+        assert_eq!(paths.len(), 4);
+        let bytes = std::fs::read(&paths[3]).unwrap();
+        assert_eq!(&bytes[..8], b"\x89PNG\r\n\x1a\n");
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(Framebuffer::new(0, 4).write_png(Vec::new()), Err(ExportError::InvalidSize)));
+        let huge = ExportOptions { width: usize::MAX, height: 2, ..options() };
+        assert!(matches!(scene().export_png_sequence(&dir, &huge), Err(ExportError::InvalidSize)));
+    }
+
+    #[test]
+    fn test_animations_advance_the_simulation() {
+        let mut bloom = scene();
+        let mut gif = Vec::new();
+        bloom.export_animation(AnimationFormat::Gif, &mut gif, &options()).unwrap();
+        assert_eq!(&gif[..6], b"GIF89a");
+
+        let mut expected = scene();
+        for _ in 0..12 {
+            expected.update(400.0, 300.0);
+        }
+        assert_eq!(bloom.snapshot(), expected.snapshot());
+
+        let mut apng = Vec::new();
+        bloom.export_animation(AnimationFormat::Apng, &mut apng, &options()).unwrap();
+        assert!(apng.windows(4).any(|chunk| chunk == b"acTL"));
+    }
+
+    #[test]
+    fn test_recording_export() {
+        let mut bloom = scene();
+        bloom.start_recording();
+        for _ in 0..7 {
+            bloom.update(400.0, 300.0);
+        }
+        let recording = bloom.stop_recording().unwrap();
+        assert_eq!(recording.frame_count(&options()), 2);
+
+        let mut apng = Vec::new();
+        recording.export_animation(AnimationFormat::Apng, &mut apng, &options()).unwrap();
+        let mut bad = options();
+        bad.width = 0;
+        assert!(matches!(recording.export_animation(AnimationFormat::Gif, Vec::new(), &bad), Err(ExportError::InvalidSize)));
+        bad = options();
+        bad.frame_stride = 10;
+        assert!(matches!(recording.export_animation(AnimationFormat::Apng, Vec::new(), &bad), Err(ExportError::NoFrames)));
+    }
+
+    #[test]
+    fn test_frame_delays_out_of_range_are_rejected() {
+        let mut bloom = scene();
+        let mut long = options();
+        // 40000 steps is ~667s: past GIF's 655.35s but still a valid APNG fraction
+        long.frame_stride = 40_000;
+        long.frames = 1;
+        assert!(matches!(bloom.export_animation(AnimationFormat::Gif, Vec::new(), &long), Err(ExportError::InvalidFrameDelay)));
+        long.frame_stride = 70_000;
+        assert!(matches!(bloom.export_animation(AnimationFormat::Apng, Vec::new(), &long), Err(ExportError::InvalidFrameDelay)));
+    }
+
+    #[test]
+    fn test_frame_counts_are_checked_before_rendering() {
+        let mut bloom = scene();
+        let mut count = options();
+        count.frames = 0;
+        assert!(matches!(bloom.export_animation(AnimationFormat::Gif, Vec::new(), &count), Err(ExportError::NoFrames)));
+        // Would take forever to render if it weren't rejected first
+        #[cfg(target_pointer_width = "64")]
+        {
+            count.frames = u32::MAX as usize + 1;
+            assert!(matches!(bloom.export_animation(AnimationFormat::Apng, Vec::new(), &count), Err(ExportError::TooManyFrames)));
+        }
+    }
+}
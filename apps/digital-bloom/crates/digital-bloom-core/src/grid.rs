@@ -0,0 +1,146 @@
+//! Uniform-grid spatial index over particle positions
+//!
+//! The canvas (plus the 50px margin particles may drift into before they are
+//! culled) is cut into square cells, and particle indices are bucketed by
+//! cell with a counting sort. A query only visits the cells its shape
+//! overlaps, so finding neighbours costs roughly the number of particles
+//! nearby rather than the number on screen.
+//!
+//! Particles outside the covered area are clamped into the edge cells, so
+//! they are still found; they just share a crowded bucket.
+
+/// Side of one grid cell, in pixels
+pub(crate) const CELL_SIZE: f64 = 40.0;
+
+/// Margin around the canvas the grid covers, matching the particle cull margin
+const MARGIN: f64 = 50.0;
+
+/// Upper bound on cells per axis, so a huge canvas can't balloon the index
+const MAX_CELLS: usize = 1024;
+
+/// Particle indices bucketed by grid cell
+///
+/// Indices refer to `ParticleStore` positions at the last `rebuild`; any
+/// later change to the store makes the grid stale until it is rebuilt.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SpatialGrid {
+    origin_x: f64,
+    origin_y: f64,
+    cols: usize,
+    rows: usize,
+    // `cells[starts[c]..starts[c + 1]]` are the particles in cell `c`
+    starts: Vec<u32>,
+    cells: Vec<u32>,
+    // Cell of each particle, kept between the counting and filling passes
+    cell_of: Vec<u32>,
+    fresh: bool,
+}
+
+impl SpatialGrid {
+    /// Whether the grid still matches the store it was built from
+    pub(crate) fn is_fresh(&self) -> bool {
+        self.fresh
+    }
+
+    /// Mark the grid out of date after particles were added, removed or moved
+    pub(crate) fn invalidate(&mut self) {
+        self.fresh = false;
+    }
+
+    /// Re-bucket every particle for a `width` x `height` canvas
+    ///
+    /// Reuses its buffers, so once they have grown to the scene's size a
+    /// rebuild never allocates.
+    pub(crate) fn rebuild(&mut self, xs: &[f64], ys: &[f64], width: f64, height: f64) {
+        self.origin_x = -MARGIN;
+        self.origin_y = -MARGIN;
+        // `as usize` saturates, so NaN or negative bounds end up as one cell
+        self.cols = (((width + MARGIN * 2.0) / CELL_SIZE).ceil() as usize).clamp(1, MAX_CELLS);
+        self.rows = (((height + MARGIN * 2.0) / CELL_SIZE).ceil() as usize).clamp(1, MAX_CELLS);
+
+        self.starts.clear();
+        self.starts.resize(self.cols * self.rows + 1, 0);
+        self.cell_of.clear();
+        for (&x, &y) in xs.iter().zip(ys) {
+            let (col, row) = self.cell_at(x, y);
+            let cell = row * self.cols + col;
+            self.cell_of.push(cell as u32);
+            self.starts[cell + 1] += 1;
+        }
+        for c in 1..self.starts.len() {
+            self.starts[c] += self.starts[c - 1];
+        }
+
+        // Fill each bucket from its start, then shift the starts back
+        self.cells.clear();
+        self.cells.resize(xs.len(), 0);
+        for (i, &cell) in self.cell_of.iter().enumerate() {
+            let slot = &mut self.starts[cell as usize];
+            self.cells[*slot as usize] = i as u32;
+            *slot += 1;
+        }
+        for c in (1..self.starts.len()).rev() {
+            self.starts[c] = self.starts[c - 1];
+        }
+        self.starts[0] = 0;
+        self.fresh = true;
+    }
+
+    /// Column and row of the cell containing (x, y), clamped into the grid
+    fn cell_at(&self, x: f64, y: f64) -> (usize, usize) {
+        let col = ((x - self.origin_x) / CELL_SIZE).floor().max(0.0) as usize;
+        let row = ((y - self.origin_y) / CELL_SIZE).floor().max(0.0) as usize;
+        (col.min(self.cols - 1), row.min(self.rows - 1))
+    }
+
+    /// Call `visit` with every particle in a cell overlapping the rectangle
+    ///
+    /// These are candidates: callers still test the exact shape.
+    pub(crate) fn for_each_in_rect(&self, min_x: f64, min_y: f64, max_x: f64, max_y: f64, mut visit: impl FnMut(usize)) {
+        if self.cells.is_empty() || !(min_x <= max_x && min_y <= max_y) {
+            return;
+        }
+        let (first_col, first_row) = self.cell_at(min_x, min_y);
+        let (last_col, last_row) = self.cell_at(max_x, max_y);
+        for row in first_row..=last_row {
+            let row_start = row * self.cols;
+            let start = self.starts[row_start + first_col] as usize;
+            let end = self.starts[row_start + last_col + 1] as usize;
+            // Cells in a row are contiguous, so the whole span is one slice
+            for &i in &self.cells[start..end] {
+                visit(i as usize);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rect_candidates_cover_every_particle_inside() {
+        let xs: Vec<f64> = (0..200).map(|i| (i * 37 % 500) as f64 - 60.0).collect();
+        let ys: Vec<f64> = (0..200).map(|i| (i * 53 % 460) as f64 - 40.0).collect();
+        let mut grid = SpatialGrid::default();
+        grid.rebuild(&xs, &ys, 400.0, 300.0);
+        assert!(grid.is_fresh());
+
+        for (min_x, min_y, max_x, max_y) in [(10.0, 10.0, 90.0, 50.0), (-100.0, -100.0, 0.0, 0.0), (350.0, 250.0, 900.0, 900.0)] {
+            let mut found = Vec::new();
+            grid.for_each_in_rect(min_x, min_y, max_x, max_y, |i| found.push(i));
+            for i in 0..xs.len() {
+                let inside = xs[i] >= min_x && xs[i] <= max_x && ys[i] >= min_y && ys[i] <= max_y;
+                assert!(!inside || found.contains(&i), "missed {i} in {min_x},{min_y}..{max_x},{max_y}");
+            }
+            // Every index shows up at most once
+            let candidates = found.len();
+            found.sort_unstable();
+            found.dedup();
+            assert_eq!(found.len(), candidates);
+        }
+
+        grid.invalidate();
+        assert!(!grid.is_fresh());
+    }
+}
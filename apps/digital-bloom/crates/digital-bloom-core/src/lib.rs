@@ -0,0 +1,57 @@
+//! Digital Bloom Core
+//!
+//! Pure-Rust particle physics engine shared by the watchOS FFI crate and the
+//! web WASM crate. All simulation logic lives here; the binding crates only
+//! translate between this API and their host (C or JavaScript).
+
+mod bloom;
+mod budget;
+mod color;
+mod config;
+#[cfg(feature = "export")]
+mod export;
+mod grid;
+mod lightning;
+mod link;
+mod mode;
+mod noise;
+mod palette;
+mod particle;
+#[cfg(feature = "render")]
+mod render;
+mod replay;
+mod rng;
+mod simd;
+mod snapshot;
+mod svg;
+mod vine;
+
+pub use bloom::{DigitalBloom, FIXED_TIMESTEP, MAX_SPAWN_COUNT};
+pub use budget::BudgetPolicy;
+pub use color::Rgba;
+pub use config::EngineConfig;
+#[cfg(feature = "export")]
+pub use export::{AnimationFormat, ExportError, ExportOptions};
+pub use lightning::Lightning;
+pub use link::{Link, LINK_WIDTH};
+pub use mode::ParticleMode;
+pub use palette::{Palette, PaletteError, BUILTIN_PALETTES};
+pub use particle::{Particle, ParticleStore};
+#[cfg(feature = "render")]
+pub use render::{Framebuffer, RenderOptions, MAX_FRAME_PIXELS};
+pub use replay::{InputEvent, Recording, RECORDING_VERSION};
+pub use rng::Rng;
+pub use snapshot::{SnapshotError, SNAPSHOT_VERSION};
+pub use svg::SvgOptions;
+pub use vine::Vine;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A 2D point in canvas coordinates
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
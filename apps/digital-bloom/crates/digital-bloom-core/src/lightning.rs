@@ -0,0 +1,85 @@
+use std::f64::consts::PI;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::color::Rgba;
+use crate::config::EngineConfig;
+use crate::rng::Rng;
+use crate::Point;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Lightning {
+    pub segments: Vec<Point>,
+    pub branches: Vec<Vec<Point>>,
+    pub color: Rgba,
+    pub line_width: f64,
+    pub life: f64,
+}
+
+impl Lightning {
+    pub fn new(rng: &mut Rng, config: &EngineConfig, start_x: f64, start_y: f64, end_x: f64, end_y: f64, color: Rgba) -> Self {
+        let mut segments = Vec::new();
+        let mut branches = Vec::new();
+
+        // Generate main bolt with jagged segments
+        let segment_count = config.lightning_segments;
+        for i in 0..=segment_count {
+            let t = i as f64 / segment_count as f64;
+            let x = start_x + (end_x - start_x) * t;
+            let y = start_y + (end_y - start_y) * t;
+
+            // Add perpendicular offset for jagged effect
+            let offset = (rng.next_f64() - 0.5) * config.lightning_jitter;
+            let dx = end_y - start_y;
+            let dy = -(end_x - start_x);
+            let len = (dx * dx + dy * dy).sqrt();
+            let nx = dx / len;
+            let ny = dy / len;
+
+            segments.push(Point {
+                x: x + nx * offset,
+                y: y + ny * offset,
+            });
+        }
+
+        // Generate 2-4 branches from random points
+        let branch_count = (rng.next_f64() * 3.0).floor() as usize + 2;
+        for _ in 0..branch_count {
+            let branch_start_idx = (rng.next_f64() * (segments.len() as f64 * 0.7)).floor() as usize + 1;
+            if branch_start_idx >= segments.len() {
+                continue;
+            }
+
+            let start_point = segments[branch_start_idx];
+            let branch_length = 5 + (rng.next_f64() * 5.0).floor() as usize;
+            let mut branch = Vec::new();
+            branch.push(start_point);
+
+            let branch_angle = rng.next_f64() * PI * 2.0;
+            for i in 1..=branch_length {
+                let dist = i as f64 * 8.0;
+                let offset = (rng.next_f64() - 0.5) * config.lightning_branch_jitter;
+                branch.push(Point {
+                    x: start_point.x + branch_angle.cos() * dist + offset,
+                    y: start_point.y + branch_angle.sin() * dist + offset,
+                });
+            }
+            branches.push(branch);
+        }
+
+        Lightning {
+            segments,
+            branches,
+            color,
+            line_width: rng.next_f64() * 2.0 + 1.5,
+            life: 1.0,
+        }
+    }
+
+    pub fn update(&mut self, config: &EngineConfig) -> bool {
+        self.life -= config.lightning_decay;
+        self.life > 0.0
+    }
+}
@@ -0,0 +1,107 @@
+//! Line links between nearby constellation stars
+//!
+//! After every step each pair of constellation particles closer than
+//! `EngineConfig::constellation_link_distance` is joined by a faint line.
+//! Links are derived from the particles, so they are never saved in
+//! snapshots; restoring one recomputes them.
+
+use crate::bloom::DigitalBloom;
+use crate::color::Rgba;
+use crate::mode::ParticleMode;
+use crate::Point;
+
+/// Stroke width of a link, in canvas units
+pub const LINK_WIDTH: f64 = 1.0;
+
+/// A line between two constellation stars
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Link {
+    /// Indices of the two stars in `DigitalBloom::particles`, `a < b`
+    pub a: usize,
+    pub b: usize,
+    pub from: Point,
+    pub to: Point,
+    /// Color of star `a`
+    pub color: Rgba,
+    /// Opacity in (0, 1]: fades with distance and with the dimmer star's life
+    pub alpha: f64,
+}
+
+impl DigitalBloom {
+    /// Links between constellation stars, as of the last step
+    pub fn links(&self) -> &[Link] {
+        &self.links
+    }
+
+    /// Recompute `links` from the current particles, reusing its buffers
+    pub(crate) fn relink(&mut self) {
+        self.links.clear();
+        let max_distance = self.config.constellation_link_distance;
+        if max_distance <= 0.0 {
+            return;
+        }
+
+        let stars = &self.particles;
+        let mut nearby = std::mem::take(&mut self.link_scratch);
+        for (a, &mode) in stars.modes().iter().enumerate() {
+            if mode != ParticleMode::Constellation {
+                continue;
+            }
+            let from = Point { x: stars.xs()[a], y: stars.ys()[a] };
+            nearby.clear();
+            stars.query_radius(from.x, from.y, max_distance, &mut nearby);
+            for &b in &nearby {
+                // Each pair once, from its lower index
+                if b <= a || stars.modes()[b] != ParticleMode::Constellation {
+                    continue;
+                }
+                let to = Point { x: stars.xs()[b], y: stars.ys()[b] };
+                let closeness = 1.0 - (to.x - from.x).hypot(to.y - from.y) / max_distance;
+                let alpha = closeness * stars.lives()[a].min(stars.lives()[b]);
+                if alpha > 0.0 {
+                    self.links.push(Link { a, b, from, to, color: stars.color(a), alpha: alpha.min(1.0) });
+                }
+            }
+        }
+        self.link_scratch = nearby;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bloom::DigitalBloom;
+    use crate::config::EngineConfig;
+    use crate::mode::ParticleMode;
+
+    #[test]
+    fn test_links_join_nearby_stars_only() {
+        let mut bloom = DigitalBloom::with_seed(21);
+        bloom.create_particles_constellation(100.0, 100.0, 12, 4.0);
+        bloom.create_particles_constellation(320.0, 320.0, 12, 4.0);
+        bloom.create_particles_burst(100.0, 100.0, 10, 4.0);
+        assert!(bloom.links().is_empty());
+        bloom.update(400.0, 400.0);
+
+        let max = bloom.config().constellation_link_distance;
+        let links = bloom.links();
+        assert!(!links.is_empty());
+        for link in links {
+            let (a, b) = (bloom.particles().get(link.a), bloom.particles().get(link.b));
+            assert!(link.a < link.b);
+            assert_eq!((a.mode(), b.mode()), (ParticleMode::Constellation, ParticleMode::Constellation));
+            assert_eq!((link.from.x, link.to.y), (a.x(), b.y()));
+            assert!((link.from.x - link.to.x).hypot(link.from.y - link.to.y) <= max);
+            assert!(link.alpha > 0.0 && link.alpha <= a.life().min(b.life()));
+        }
+        // The two clusters are too far apart to connect
+        assert!(links.iter().all(|l| (l.from.x < 210.0) == (l.to.x < 210.0)));
+
+        // Restored engines come back with their links
+        let restored = DigitalBloom::from_snapshot(&bloom.snapshot()).unwrap();
+        assert_eq!(restored.links(), bloom.links());
+
+        bloom.set_config(EngineConfig { constellation_link_distance: 0.0, ..EngineConfig::default() });
+        bloom.update(400.0, 400.0);
+        assert!(bloom.links().is_empty());
+    }
+}
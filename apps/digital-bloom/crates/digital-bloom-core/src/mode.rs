@@ -0,0 +1,58 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// Brush mode shared by the engine, the C header and the TypeScript bindings
+///
+/// The discriminants are part of the FFI contract and must not change.
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub enum ParticleMode {
+    Vine = 0,
+    Gravity = 1,
+    Bounce = 2,
+    Burst = 3,
+    Lightning = 4,
+    Constellation = 5,
+    Vortex = 6,
+    Fireworks = 7,
+    Smoke = 8,
+    Boids = 9,
+}
+
+impl TryFrom<u8> for ParticleMode {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ParticleMode::Vine),
+            1 => Ok(ParticleMode::Gravity),
+            2 => Ok(ParticleMode::Bounce),
+            3 => Ok(ParticleMode::Burst),
+            4 => Ok(ParticleMode::Lightning),
+            5 => Ok(ParticleMode::Constellation),
+            6 => Ok(ParticleMode::Vortex),
+            7 => Ok(ParticleMode::Fireworks),
+            8 => Ok(ParticleMode::Smoke),
+            9 => Ok(ParticleMode::Boids),
+            _ => Err(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mode_round_trip() {
+        for value in 0..=9u8 {
+            let mode = ParticleMode::try_from(value).unwrap();
+            assert_eq!(mode as u8, value);
+        }
+        assert_eq!(ParticleMode::try_from(10), Err(10));
+    }
+}
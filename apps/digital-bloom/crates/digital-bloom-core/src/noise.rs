@@ -0,0 +1,174 @@
+//! 3D simplex noise and the curl field smoke particles drift through
+//!
+//! The field is the curl of a scalar noise potential ψ(x, y, t): velocity is
+//! (∂ψ/∂y, −∂ψ/∂x), which is divergence-free, so smoke swirls and folds
+//! without bunching up or thinning out. The third noise axis is time, which
+//! makes the field evolve smoothly.
+//!
+//! Everything is plain f64 arithmetic on a fixed permutation table, so the
+//! field is identical on every platform and replays stay deterministic.
+
+/// Ken Perlin's reference permutation
+#[rustfmt::skip]
+const PERM: [u8; 256] = [
+    151, 160, 137, 91, 90, 15, 131, 13, 201, 95, 96, 53, 194, 233, 7, 225,
+    140, 36, 103, 30, 69, 142, 8, 99, 37, 240, 21, 10, 23, 190, 6, 148,
+    247, 120, 234, 75, 0, 26, 197, 62, 94, 252, 219, 203, 117, 35, 11, 32,
+    57, 177, 33, 88, 237, 149, 56, 87, 174, 20, 125, 136, 171, 168, 68, 175,
+    74, 165, 71, 134, 139, 48, 27, 166, 77, 146, 158, 231, 83, 111, 229, 122,
+    60, 211, 133, 230, 220, 105, 92, 41, 55, 46, 245, 40, 244, 102, 143, 54,
+    65, 25, 63, 161, 1, 216, 80, 73, 209, 76, 132, 187, 208, 89, 18, 169,
+    200, 196, 135, 130, 116, 188, 159, 86, 164, 100, 109, 198, 173, 186, 3, 64,
+    52, 217, 226, 250, 124, 123, 5, 202, 38, 147, 118, 126, 255, 82, 85, 212,
+    207, 206, 59, 227, 47, 16, 58, 17, 182, 189, 28, 42, 223, 183, 170, 213,
+    119, 248, 152, 2, 44, 154, 163, 70, 221, 153, 101, 155, 167, 43, 172, 9,
+    129, 22, 39, 253, 19, 98, 108, 110, 79, 113, 224, 232, 178, 185, 112, 104,
+    218, 246, 97, 228, 251, 34, 242, 193, 238, 210, 144, 12, 191, 179, 162, 241,
+    81, 51, 145, 235, 249, 14, 239, 107, 49, 192, 214, 31, 181, 199, 106, 157,
+    184, 84, 204, 176, 115, 121, 50, 45, 127, 4, 150, 254, 138, 236, 205, 93,
+    222, 114, 67, 29, 24, 72, 243, 141, 128, 195, 78, 66, 215, 61, 156, 180,
+];
+
+/// Gradients toward the midpoints of a cube's 12 edges
+const GRADIENTS: [[f64; 3]; 12] = [
+    [1.0, 1.0, 0.0],
+    [-1.0, 1.0, 0.0],
+    [1.0, -1.0, 0.0],
+    [-1.0, -1.0, 0.0],
+    [1.0, 0.0, 1.0],
+    [-1.0, 0.0, 1.0],
+    [1.0, 0.0, -1.0],
+    [-1.0, 0.0, -1.0],
+    [0.0, 1.0, 1.0],
+    [0.0, -1.0, 1.0],
+    [0.0, 1.0, -1.0],
+    [0.0, -1.0, -1.0],
+];
+
+/// Scales the raw simplex sum into [-1, 1]
+///
+/// One corner adds at most (0.5 - r²)⁴·√2·r ≈ 0.013007 (at r² = 1/18), which
+/// this maps to ≈0.99. The dense sweep in
+/// `test_simplex_stays_in_range_without_clamping` finds no point where the
+/// corners together get past that, so there is no clamp to flatten the peaks
+/// and break the curl's derivatives there.
+const NORMALIZE: f64 = 76.0;
+
+/// Noise units the smoke field moves through per fixed step
+const FIELD_SPEED: f64 = 0.004;
+
+/// Lattice gradient for integer coordinates (wraps every 256 cells)
+fn gradient(i: i64, j: i64, k: i64) -> [f64; 3] {
+    let p = |n: i64| PERM[(n & 255) as usize] as i64;
+    GRADIENTS[p(i + p(j + p(k))) as usize % 12]
+}
+
+/// Simplex noise at (x, y, z), in [-1, 1]
+pub(crate) fn simplex3(x: f64, y: f64, z: f64) -> f64 {
+    const SKEW: f64 = 1.0 / 3.0;
+    const UNSKEW: f64 = 1.0 / 6.0;
+
+    // Find the simplex cell containing the point
+    let s = (x + y + z) * SKEW;
+    let (i, j, k) = ((x + s).floor(), (y + s).floor(), (z + s).floor());
+    let t = (i + j + k) * UNSKEW;
+    let (x0, y0, z0) = (x - (i - t), y - (j - t), z - (k - t));
+
+    // Order the offsets to pick which of the cube's six tetrahedra it's in
+    let (second, third) = if x0 >= y0 {
+        if y0 >= z0 {
+            ([1, 0, 0], [1, 1, 0])
+        } else if x0 >= z0 {
+            ([1, 0, 0], [1, 0, 1])
+        } else {
+            ([0, 0, 1], [1, 0, 1])
+        }
+    } else if y0 < z0 {
+        ([0, 0, 1], [0, 1, 1])
+    } else if x0 < z0 {
+        ([0, 1, 0], [0, 1, 1])
+    } else {
+        ([0, 1, 0], [1, 1, 0])
+    };
+
+    let (i, j, k) = (i as i64, j as i64, k as i64);
+    let mut total = 0.0;
+    for (n, corner) in [[0, 0, 0], second, third, [1, 1, 1]].into_iter().enumerate() {
+        let unskew = n as f64 * UNSKEW;
+        let dx = x0 - corner[0] as f64 + unskew;
+        let dy = y0 - corner[1] as f64 + unskew;
+        let dz = z0 - corner[2] as f64 + unskew;
+        // A radius of 0.5 keeps each corner's falloff inside its neighbouring
+        // simplices, so the noise (and the curl taken from it) is continuous
+        let falloff = 0.5 - dx * dx - dy * dy - dz * dz;
+        if falloff > 0.0 {
+            let g = gradient(i + corner[0], j + corner[1], k + corner[2]);
+            total += falloff.powi(4) * (g[0] * dx + g[1] * dy + g[2] * dz);
+        }
+    }
+    total * NORMALIZE
+}
+
+/// Velocity of the curl field at (x, y) and time `t`, all in noise units
+///
+/// Typical magnitudes are around 1-3; callers scale to pixels per step.
+pub(crate) fn curl(x: f64, y: f64, t: f64) -> (f64, f64) {
+    const EPS: f64 = 1e-3;
+    let dpsi_dx = (simplex3(x + EPS, y, t) - simplex3(x - EPS, y, t)) / (2.0 * EPS);
+    let dpsi_dy = (simplex3(x, y + EPS, t) - simplex3(x, y - EPS, t)) / (2.0 * EPS);
+    (dpsi_dy, -dpsi_dx)
+}
+
+/// Time coordinate of the smoke field after `steps` fixed steps
+///
+/// The seed shifts the starting point so every engine gets its own field.
+pub(crate) fn field_time(steps: u64, seed: u64) -> f64 {
+    (seed % 4096) as f64 * 16.0 + steps as f64 * FIELD_SPEED
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simplex_range_and_continuity() {
+        let mut peak: f64 = 0.0;
+        for i in 0..4000 {
+            let (x, y, z) = (i as f64 * 0.137, i as f64 * -0.071, i as f64 * 0.029);
+            let n = simplex3(x, y, z);
+            peak = peak.max(n.abs());
+            assert!((n - simplex3(x + 1e-6, y, z)).abs() < 1e-3);
+        }
+        assert!(peak > 0.5 && peak < 1.0, "peak {peak}");
+        assert_eq!(simplex3(0.0, 0.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_simplex_stays_in_range_without_clamping() {
+        // Dense sweep over a few cells, backing the bound claimed on NORMALIZE
+        let mut peak: f64 = 0.0;
+        for i in 0..60 {
+            for j in 0..60 {
+                for k in 0..60 {
+                    let (x, y, z) = (i as f64 * 0.05, j as f64 * 0.05 + 7.3, k as f64 * 0.05 - 2.1);
+                    peak = peak.max(simplex3(x, y, z).abs());
+                }
+            }
+        }
+        assert!(peak > 0.7 && peak < 0.99, "peak {peak}");
+    }
+
+    #[test]
+    fn test_curl_is_divergence_free() {
+        let h = 1e-3;
+        for i in 0..200 {
+            let (x, y, t) = (i as f64 * 0.31, i as f64 * 0.17 + 2.0, i as f64 * 0.05);
+            let divergence = (curl(x + h, y, t).0 - curl(x - h, y, t).0) / (2.0 * h)
+                + (curl(x, y + h, t).1 - curl(x, y - h, t).1) / (2.0 * h);
+            let (vx, vy) = curl(x, y, t);
+            // Finite differences leave only rounding noise next to the field itself
+            assert!(divergence.abs() < 1e-3 * (1.0 + vx.hypot(vy)), "divergence {divergence} at {i}");
+        }
+    }
+}
+
@@ -0,0 +1,190 @@
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::color::Rgba;
+use crate::rng::Rng;
+
+/// Names accepted by `Palette::builtin`, in display order
+pub const BUILTIN_PALETTES: &[&str] = &["bloom", "sunset", "ocean", "forest", "mono"];
+
+/// Set of colors new elements are drawn from, with optional relative weights
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "RawPalette"))]
+pub struct Palette {
+    colors: Vec<Rgba>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    weights: Option<Vec<f64>>,
+}
+
+// Unvalidated wire form, so deserializing can't bypass `validated`
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct RawPalette {
+    colors: Vec<Rgba>,
+    #[serde(default)]
+    weights: Option<Vec<f64>>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawPalette> for Palette {
+    type Error = PaletteError;
+
+    fn try_from(raw: RawPalette) -> Result<Self, Self::Error> {
+        Palette { colors: raw.colors, weights: raw.weights }.validated()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PaletteError {
+    /// A palette needs at least one color
+    Empty,
+    /// `weights` must have one entry per color
+    WeightCountMismatch { colors: usize, weights: usize },
+    /// Weights must be finite, non-negative and not all zero
+    InvalidWeights,
+    /// No built-in palette with this name
+    UnknownName(String),
+}
+
+impl fmt::Display for PaletteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaletteError::Empty => write!(f, "palette has no colors"),
+            PaletteError::WeightCountMismatch { colors, weights } => {
+                write!(f, "palette has {colors} colors but {weights} weights")
+            }
+            PaletteError::InvalidWeights => write!(f, "palette weights must be non-negative and not all zero"),
+            PaletteError::UnknownName(name) => write!(f, "unknown palette {name:?}"),
+        }
+    }
+}
+
+impl std::error::Error for PaletteError {}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette::builtin("bloom").unwrap()
+    }
+}
+
+impl Palette {
+    /// Palette where every color is equally likely
+    pub fn new(colors: Vec<Rgba>) -> Result<Self, PaletteError> {
+        Palette { colors, weights: None }.validated()
+    }
+
+    /// Palette where color `i` is picked with probability `weights[i] / sum(weights)`
+    pub fn with_weights(colors: Vec<Rgba>, weights: Vec<f64>) -> Result<Self, PaletteError> {
+        Palette { colors, weights: Some(weights) }.validated()
+    }
+
+    /// Look up one of the `BUILTIN_PALETTES` by name
+    pub fn builtin(name: &str) -> Result<Self, PaletteError> {
+        let hex: &[u32] = match name {
+            "bloom" => &[0xff69b4, 0x00ffff, 0x7fff00, 0xff00ff, 0xff8c00, 0xadff2f, 0xd8bfd8],
+            "sunset" => &[0xff4e50, 0xfc913a, 0xf9d423, 0xff6f91, 0xc06c84],
+            "ocean" => &[0x00b4d8, 0x48cae4, 0x90e0ef, 0x0077b6, 0xcaf0f8],
+            "forest" => &[0x2d6a4f, 0x40916c, 0x52b788, 0x95d5b2, 0xd8f3dc],
+            "mono" => &[0xffffff, 0xd9d9d9, 0xa6a6a6],
+            _ => return Err(PaletteError::UnknownName(name.to_string())),
+        };
+        let colors = hex
+            .iter()
+            .map(|c| Rgba::rgb((c >> 16) as u8, (c >> 8) as u8, *c as u8))
+            .collect();
+        Palette::new(colors)
+    }
+
+    fn validated(self) -> Result<Self, PaletteError> {
+        if self.colors.is_empty() {
+            return Err(PaletteError::Empty);
+        }
+        if let Some(weights) = &self.weights {
+            if weights.len() != self.colors.len() {
+                return Err(PaletteError::WeightCountMismatch {
+                    colors: self.colors.len(),
+                    weights: weights.len(),
+                });
+            }
+            if weights.iter().any(|w| !w.is_finite() || *w < 0.0) || weights.iter().sum::<f64>() <= 0.0 {
+                return Err(PaletteError::InvalidWeights);
+            }
+        }
+        Ok(self)
+    }
+
+    pub fn colors(&self) -> &[Rgba] {
+        &self.colors
+    }
+
+    pub fn weights(&self) -> Option<&[f64]> {
+        self.weights.as_deref()
+    }
+
+    /// Pick a color, honouring the weights if any
+    pub fn pick(&self, rng: &mut Rng) -> Rgba {
+        let Some(weights) = &self.weights else {
+            let index = (rng.next_f64() * self.colors.len() as f64).floor() as usize;
+            return self.colors[index];
+        };
+
+        let target = rng.next_f64() * weights.iter().sum::<f64>();
+        self.colors[weighted_index(weights, target)]
+    }
+}
+
+/// Index of the bucket `target` falls in, walking `weights` in order
+fn weighted_index(weights: &[f64], mut target: f64) -> usize {
+    for (index, weight) in weights.iter().enumerate() {
+        if target < *weight {
+            return index;
+        }
+        target -= weight;
+    }
+    // Float rounding can leave a sliver past the last bucket; land in the
+    // last one that can actually be picked (validation guarantees one)
+    weights.iter().rposition(|w| *w > 0.0).unwrap_or(weights.len() - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtins_are_valid() {
+        for name in BUILTIN_PALETTES {
+            assert!(Palette::builtin(name).is_ok(), "{name}");
+        }
+        assert_eq!(Palette::builtin("nope"), Err(PaletteError::UnknownName("nope".to_string())));
+    }
+
+    #[test]
+    fn test_weighted_pick() {
+        let red = Rgba::rgb(255, 0, 0);
+        let blue = Rgba::rgb(0, 0, 255);
+        let palette = Palette::with_weights(vec![red, blue], vec![0.0, 1.0]).unwrap();
+        let mut rng = Rng::new(9);
+        assert!((0..100).all(|_| palette.pick(&mut rng) == blue));
+    }
+
+    #[test]
+    fn test_rounding_overshoot_skips_zero_weights() {
+        assert_eq!(weighted_index(&[1.0, 2.0, 0.0], 3.0), 1);
+        assert_eq!(weighted_index(&[1.0, 0.0, 0.0], 1.0 + 1e-12), 0);
+        assert_eq!(weighted_index(&[0.0, 2.0], 0.5), 1);
+    }
+
+    #[test]
+    fn test_rejects_bad_weights() {
+        let colors = vec![Rgba::WHITE];
+        assert_eq!(Palette::new(Vec::new()), Err(PaletteError::Empty));
+        assert!(matches!(
+            Palette::with_weights(colors.clone(), vec![1.0, 2.0]),
+            Err(PaletteError::WeightCountMismatch { .. })
+        ));
+        assert_eq!(Palette::with_weights(colors, vec![0.0]), Err(PaletteError::InvalidWeights));
+    }
+}
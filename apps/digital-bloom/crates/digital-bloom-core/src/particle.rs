@@ -0,0 +1,785 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::color::Rgba;
+use crate::config::EngineConfig;
+use crate::grid::SpatialGrid;
+use crate::mode::ParticleMode;
+use crate::noise;
+use crate::simd::{self, Motion};
+use crate::Point;
+
+/// What a fireworks particle does when it goes off
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(tag = "type", rename_all = "snake_case"))]
+pub(crate) enum Charge {
+    /// Nothing left to burst
+    #[default]
+    None,
+    /// Rising shell; bursts into `sparks` sparks at the top of its climb
+    Shell { sparks: u32 },
+    /// Spark that crackles into a small sub-burst once its life falls to `fuse`
+    Crackle { fuse: f64 },
+}
+
+/// One particle, as spawned or read back out of a `ParticleStore`
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Particle {
+    pub(crate) x: f64,
+    pub(crate) y: f64,
+    pub(crate) vx: f64,
+    pub(crate) vy: f64,
+    // Position before the last step, for render interpolation
+    pub(crate) prev_x: f64,
+    pub(crate) prev_y: f64,
+    pub(crate) color: Rgba,
+    pub(crate) size: f64,
+    pub(crate) mode: ParticleMode,
+    pub(crate) life: f64,
+    // Vortex-specific fields
+    pub(crate) origin_x: f64,
+    pub(crate) origin_y: f64,
+    pub(crate) angle: f64,
+    pub(crate) radius: f64,
+    // Fireworks-specific field
+    pub(crate) charge: Charge,
+}
+
+impl Particle {
+    pub fn new(x: f64, y: f64, vx: f64, vy: f64, color: Rgba, size: f64, mode: ParticleMode) -> Self {
+        Particle {
+            x,
+            y,
+            vx,
+            vy,
+            prev_x: x,
+            prev_y: y,
+            color,
+            size,
+            mode,
+            life: 1.0,
+            origin_x: x,
+            origin_y: y,
+            angle: 0.0,
+            radius: 0.0,
+            charge: Charge::None,
+        }
+    }
+
+    /// Create a particle orbiting (origin_x, origin_y) at the given angle and radius
+    pub fn new_vortex(origin_x: f64, origin_y: f64, angle: f64, radius: f64, color: Rgba, size: f64) -> Self {
+        let x = origin_x + angle.cos() * radius;
+        let y = origin_y + angle.sin() * radius;
+        Particle {
+            x,
+            y,
+            vx: angle.cos() * 2.0,
+            vy: angle.sin() * 2.0,
+            prev_x: x,
+            prev_y: y,
+            color,
+            size,
+            mode: ParticleMode::Vortex,
+            life: 1.0,
+            origin_x,
+            origin_y,
+            angle,
+            radius,
+            charge: Charge::None,
+        }
+    }
+
+    /// Arm a fireworks particle
+    pub(crate) fn with_charge(mut self, charge: Charge) -> Self {
+        self.charge = charge;
+        self
+    }
+
+    // FFI-friendly getter methods
+    pub fn x(&self) -> f64 { self.x }
+    pub fn y(&self) -> f64 { self.y }
+    pub fn prev_x(&self) -> f64 { self.prev_x }
+    pub fn prev_y(&self) -> f64 { self.prev_y }
+    pub fn size(&self) -> f64 { self.size }
+    pub fn life(&self) -> f64 { self.life }
+    pub fn color(&self) -> Rgba { self.color }
+    pub fn mode(&self) -> ParticleMode { self.mode }
+}
+
+/// Struct-of-arrays particle storage
+///
+/// Each field lives in its own column so the per-step motion update runs as a
+/// straight loop over contiguous f64s (see `simd`). Dead particles are removed
+/// with `swap_remove`, so storage order is not spawn order; `born` keeps the
+/// spawn sequence for budget policies that drop the oldest first.
+///
+/// Colors are stored once in a small table and referenced by index.
+///
+/// A spatial grid over the positions is rebuilt every step and answers
+/// `query_radius` / `query_rect` without scanning every particle.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "RawParticleStore", try_from = "RawParticleStore"))]
+pub struct ParticleStore {
+    x: Vec<f64>,
+    y: Vec<f64>,
+    vx: Vec<f64>,
+    vy: Vec<f64>,
+    prev_x: Vec<f64>,
+    prev_y: Vec<f64>,
+    life: Vec<f64>,
+    size: Vec<f64>,
+    color: Vec<u32>,
+    mode: Vec<ParticleMode>,
+    origin_x: Vec<f64>,
+    origin_y: Vec<f64>,
+    angle: Vec<f64>,
+    radius: Vec<f64>,
+    charge: Vec<Charge>,
+    born: Vec<u64>,
+    // Per-particle coefficients derived from the mode and the engine config
+    // (see `retune`), so the motion loop needs no branches
+    drag: Vec<f64>,
+    lift: Vec<f64>,
+    decay: Vec<f64>,
+    colors: Vec<Rgba>,
+    pub(crate) next_born: u64,
+    // Reused index buffer for budget enforcement and flocking
+    scratch: Vec<usize>,
+    // Reused per-boid steering buffer
+    steer: Vec<(f64, f64)>,
+    // Neighbour index; stale whenever particles are added, removed or moved
+    grid: SpatialGrid,
+}
+
+/// Per-step inputs to `ParticleStore::update` that come from the engine
+/// rather than the config
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct StepContext {
+    pub width: f64,
+    pub height: f64,
+    /// Decay multiplier from the budget policy (1.0 = normal)
+    pub load_factor: f64,
+    /// Time coordinate of the smoke field
+    pub field_time: f64,
+    /// Point boids are drawn toward, if any
+    pub pointer: Option<Point>,
+}
+
+/// Noise units per pixel for the smoke field; swirls are roughly 100px across
+const SMOKE_SCALE: f64 = 0.01;
+/// Fraction of the gap to the field velocity a smoke puff closes each step
+const SMOKE_EASE: f64 = 0.08;
+/// Upward drift added to the field, in pixels per step, so smoke rises
+const SMOKE_RISE: f64 = 0.3;
+
+/// Boids closer than this to an edge start turning back, in pixels
+const BOID_MARGIN: f64 = 30.0;
+/// Velocity change per step that turns a boid away from an edge
+const BOID_TURN: f64 = 0.2;
+
+/// Motion coefficients for one particle: (drag, lift, decay)
+fn coefficients(mode: ParticleMode, config: &EngineConfig) -> (f64, f64, f64) {
+    // Vortex particles move on their orbit and boids hold their own speed
+    let drag = if matches!(mode, ParticleMode::Vortex | ParticleMode::Boids) { 1.0 } else { config.friction };
+    let lift = if matches!(mode, ParticleMode::Gravity | ParticleMode::Fireworks) { config.gravity } else { 0.0 };
+    (drag, lift, config.decay_for(mode))
+}
+
+impl ParticleStore {
+    pub fn len(&self) -> usize {
+        self.x.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x.is_empty()
+    }
+
+    /// Particle at `index`; panics if out of range
+    pub fn get(&self, index: usize) -> Particle {
+        Particle {
+            x: self.x[index],
+            y: self.y[index],
+            vx: self.vx[index],
+            vy: self.vy[index],
+            prev_x: self.prev_x[index],
+            prev_y: self.prev_y[index],
+            color: self.colors[self.color[index] as usize],
+            size: self.size[index],
+            mode: self.mode[index],
+            life: self.life[index],
+            origin_x: self.origin_x[index],
+            origin_y: self.origin_y[index],
+            angle: self.angle[index],
+            radius: self.radius[index],
+            charge: self.charge[index],
+        }
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = Particle> + '_ {
+        (0..self.len()).map(|i| self.get(i))
+    }
+
+    pub fn xs(&self) -> &[f64] {
+        &self.x
+    }
+
+    pub fn ys(&self) -> &[f64] {
+        &self.y
+    }
+
+    pub fn prev_xs(&self) -> &[f64] {
+        &self.prev_x
+    }
+
+    pub fn prev_ys(&self) -> &[f64] {
+        &self.prev_y
+    }
+
+    pub fn lives(&self) -> &[f64] {
+        &self.life
+    }
+
+    pub fn sizes(&self) -> &[f64] {
+        &self.size
+    }
+
+    pub fn modes(&self) -> &[ParticleMode] {
+        &self.mode
+    }
+
+    pub fn color(&self, index: usize) -> Rgba {
+        self.colors[self.color[index] as usize]
+    }
+
+    /// Append the indices of particles within `radius` of (x, y) to `out`, in ascending order
+    ///
+    /// Uses the spatial grid when it is up to date and falls back to a full
+    /// scan otherwise (e.g. right after spawning, before the next step).
+    pub fn query_radius(&self, x: f64, y: f64, radius: f64, out: &mut Vec<usize>) {
+        let from = out.len();
+        let radius_sq = radius * radius;
+        let mut test = |i: usize| {
+            let (dx, dy) = (self.x[i] - x, self.y[i] - y);
+            if dx * dx + dy * dy <= radius_sq {
+                out.push(i);
+            }
+        };
+        if self.grid.is_fresh() {
+            self.grid.for_each_in_rect(x - radius, y - radius, x + radius, y + radius, &mut test);
+        } else {
+            (0..self.len()).for_each(&mut test);
+        }
+        out[from..].sort_unstable();
+    }
+
+    /// Append the indices of particles inside the rectangle (edges included) to `out`, in ascending order
+    pub fn query_rect(&self, min_x: f64, min_y: f64, max_x: f64, max_y: f64, out: &mut Vec<usize>) {
+        let from = out.len();
+        let mut test = |i: usize| {
+            if self.x[i] >= min_x && self.x[i] <= max_x && self.y[i] >= min_y && self.y[i] <= max_y {
+                out.push(i);
+            }
+        };
+        if self.grid.is_fresh() {
+            self.grid.for_each_in_rect(min_x, min_y, max_x, max_y, &mut test);
+        } else {
+            (0..self.len()).for_each(&mut test);
+        }
+        out[from..].sort_unstable();
+    }
+
+    /// Rebuild the spatial grid if anything changed since it was last built
+    pub(crate) fn reindex(&mut self, width: f64, height: f64) {
+        if !self.grid.is_fresh() {
+            self.grid.rebuild(&self.x, &self.y, width, height);
+        }
+    }
+
+    /// Whether the fireworks particle at `index` should burst now
+    pub(crate) fn is_charge_ready(&self, index: usize) -> bool {
+        match self.charge[index] {
+            Charge::None => false,
+            // Screen y grows downward, so the climb ends once vy turns positive
+            Charge::Shell { .. } => self.vy[index] >= 0.0,
+            Charge::Crackle { fuse } => self.life[index] <= fuse,
+        }
+    }
+
+    /// Spawn sequence numbers; lower is older
+    pub(crate) fn born(&self) -> &[u64] {
+        &self.born
+    }
+
+    pub(crate) fn push(&mut self, particle: Particle, config: &EngineConfig) {
+        let born = self.next_born;
+        self.push_with_born(particle, born, config);
+    }
+
+    /// Insert a particle with a known spawn sequence number, e.g. from a snapshot
+    pub(crate) fn push_with_born(&mut self, p: Particle, born: u64, config: &EngineConfig) {
+        let color = match self.colors.iter().position(|&c| c == p.color) {
+            Some(index) => index,
+            None => {
+                self.colors.push(p.color);
+                self.colors.len() - 1
+            }
+        };
+        let (drag, lift, decay) = coefficients(p.mode, config);
+
+        self.x.push(p.x);
+        self.y.push(p.y);
+        self.vx.push(p.vx);
+        self.vy.push(p.vy);
+        self.prev_x.push(p.prev_x);
+        self.prev_y.push(p.prev_y);
+        self.life.push(p.life);
+        self.size.push(p.size);
+        self.color.push(color as u32);
+        self.mode.push(p.mode);
+        self.origin_x.push(p.origin_x);
+        self.origin_y.push(p.origin_y);
+        self.angle.push(p.angle);
+        self.radius.push(p.radius);
+        self.charge.push(p.charge);
+        self.born.push(born);
+        self.drag.push(drag);
+        self.lift.push(lift);
+        self.decay.push(decay);
+        self.next_born = self.next_born.max(born + 1);
+        self.grid.invalidate();
+    }
+
+    /// Recompute the per-particle coefficients after the config changed
+    pub(crate) fn retune(&mut self, config: &EngineConfig) {
+        for (i, &mode) in self.mode.iter().enumerate() {
+            (self.drag[i], self.lift[i], self.decay[i]) = coefficients(mode, config);
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        for column in [
+            &mut self.x,
+            &mut self.y,
+            &mut self.vx,
+            &mut self.vy,
+            &mut self.prev_x,
+            &mut self.prev_y,
+            &mut self.life,
+            &mut self.size,
+            &mut self.origin_x,
+            &mut self.origin_y,
+            &mut self.angle,
+            &mut self.radius,
+            &mut self.drag,
+            &mut self.lift,
+            &mut self.decay,
+        ] {
+            column.clear();
+        }
+        self.color.clear();
+        self.mode.clear();
+        self.charge.clear();
+        self.born.clear();
+        self.colors.clear();
+        self.grid.invalidate();
+    }
+
+    /// Remove the particle at `index`, moving the last one into its slot
+    pub(crate) fn swap_remove(&mut self, index: usize) {
+        for column in [
+            &mut self.x,
+            &mut self.y,
+            &mut self.vx,
+            &mut self.vy,
+            &mut self.prev_x,
+            &mut self.prev_y,
+            &mut self.life,
+            &mut self.size,
+            &mut self.origin_x,
+            &mut self.origin_y,
+            &mut self.angle,
+            &mut self.radius,
+            &mut self.drag,
+            &mut self.lift,
+            &mut self.decay,
+        ] {
+            column.swap_remove(index);
+        }
+        self.color.swap_remove(index);
+        self.mode.swap_remove(index);
+        self.charge.swap_remove(index);
+        self.born.swap_remove(index);
+        self.grid.invalidate();
+        if self.is_empty() {
+            // Nothing references the color table any more
+            self.colors.clear();
+        }
+    }
+
+    /// Drop color table entries no particle references any more
+    ///
+    /// Only `clear` and emptying the store reset the table otherwise, so a
+    /// long session that keeps switching palettes would grow it without
+    /// bound; call this when the palette changes.
+    pub(crate) fn compact_colors(&mut self) {
+        const UNUSED: usize = usize::MAX;
+        let mut remap = std::mem::take(&mut self.scratch);
+        remap.clear();
+        remap.resize(self.colors.len(), UNUSED);
+        for &c in &self.color {
+            remap[c as usize] = 0;
+        }
+        // Survivors keep their order, so each moves down or stays put
+        let mut kept = 0;
+        for (old, slot) in remap.iter_mut().enumerate() {
+            if *slot != UNUSED {
+                *slot = kept;
+                self.colors[kept] = self.colors[old];
+                kept += 1;
+            }
+        }
+        self.colors.truncate(kept);
+        for c in &mut self.color {
+            *c = remap[*c as usize] as u32;
+        }
+        self.scratch = remap;
+    }
+
+    /// Remove every particle in `victims` (distinct indices); reorders `victims`
+    pub(crate) fn remove_indices(&mut self, victims: &mut [usize]) {
+        // Highest first, so each swap_remove only moves a survivor
+        victims.sort_unstable_by(|a, b| b.cmp(a));
+        for &i in victims.iter() {
+            self.swap_remove(i);
+        }
+    }
+
+    /// Borrow the reusable index buffer; hand it back with `put_scratch`
+    pub(crate) fn take_scratch(&mut self) -> Vec<usize> {
+        std::mem::take(&mut self.scratch)
+    }
+
+    pub(crate) fn put_scratch(&mut self, scratch: Vec<usize>) {
+        self.scratch = scratch;
+    }
+
+    /// Steer every boid by separation, alignment and cohesion with the
+    /// flockmates it can see, plus the pointer and the canvas edges
+    ///
+    /// All steering is computed from the current velocities before any is
+    /// applied, so the result doesn't depend on storage order.
+    fn flock(&mut self, config: &EngineConfig, ctx: &StepContext) {
+        let mut boids = self.take_scratch();
+        boids.clear();
+        boids.extend((0..self.len()).filter(|&i| self.mode[i] == ParticleMode::Boids));
+        if boids.is_empty() {
+            self.put_scratch(boids);
+            return;
+        }
+
+        self.reindex(ctx.width, ctx.height);
+        let mut steer = std::mem::take(&mut self.steer);
+        steer.clear();
+        let radius = config.boid_view_radius;
+        let view = radius * radius;
+        for &i in &boids {
+            let (mut push_x, mut push_y) = (0.0, 0.0);
+            let (mut sum_vx, mut sum_vy, mut sum_x, mut sum_y) = (0.0, 0.0, 0.0, 0.0);
+            let mut seen = 0usize;
+            let (x, y) = (self.x[i], self.y[i]);
+            self.grid.for_each_in_rect(x - radius, y - radius, x + radius, y + radius, |j| {
+                let (dx, dy) = (x - self.x[j], y - self.y[j]);
+                let dist_sq = dx * dx + dy * dy;
+                if j == i || dist_sq > view || self.mode[j] != ParticleMode::Boids {
+                    return;
+                }
+                // Push falls off with the square of the distance, capped for
+                // boids sitting on top of each other
+                let dist_sq = dist_sq.max(1.0);
+                push_x += dx / dist_sq;
+                push_y += dy / dist_sq;
+                sum_vx += self.vx[j];
+                sum_vy += self.vy[j];
+                sum_x += self.x[j];
+                sum_y += self.y[j];
+                seen += 1;
+            });
+
+            let (mut ax, mut ay) = (0.0, 0.0);
+            if seen > 0 {
+                let n = seen as f64;
+                ax += push_x * config.boid_separation
+                    + (sum_vx / n - self.vx[i]) * config.boid_alignment
+                    + (sum_x / n - self.x[i]) * config.boid_cohesion;
+                ay += push_y * config.boid_separation
+                    + (sum_vy / n - self.vy[i]) * config.boid_alignment
+                    + (sum_y / n - self.y[i]) * config.boid_cohesion;
+            }
+            if let Some(pointer) = ctx.pointer {
+                let (dx, dy) = (pointer.x - self.x[i], pointer.y - self.y[i]);
+                let dist = dx.hypot(dy);
+                if dist > 0.0 {
+                    ax += dx / dist * config.boid_pointer_pull;
+                    ay += dy / dist * config.boid_pointer_pull;
+                }
+            }
+            if self.x[i] < BOID_MARGIN {
+                ax += BOID_TURN;
+            } else if self.x[i] > ctx.width - BOID_MARGIN {
+                ax -= BOID_TURN;
+            }
+            if self.y[i] < BOID_MARGIN {
+                ay += BOID_TURN;
+            } else if self.y[i] > ctx.height - BOID_MARGIN {
+                ay -= BOID_TURN;
+            }
+            steer.push((ax, ay));
+        }
+
+        // Keep every boid between half and full top speed so the flock never stalls
+        let max_speed = config.boid_max_speed;
+        for (&i, &(ax, ay)) in boids.iter().zip(&steer) {
+            let (vx, vy) = (self.vx[i] + ax, self.vy[i] + ay);
+            let speed = vx.hypot(vy);
+            let scale = if speed > max_speed {
+                max_speed / speed
+            } else if speed > 0.0 && speed < max_speed * 0.5 {
+                max_speed * 0.5 / speed
+            } else {
+                1.0
+            };
+            self.vx[i] = vx * scale;
+            self.vy[i] = vy * scale;
+        }
+        // Steering only changed velocities, so the grid is still current
+
+        self.steer = steer;
+        self.put_scratch(boids);
+    }
+
+    /// Advance every particle one fixed step and drop the dead ones
+    ///
+    /// A particle dies once its life runs out or it leaves the canvas by more
+    /// than 50px.
+    pub(crate) fn update(&mut self, config: &EngineConfig, ctx: &StepContext) {
+        let StepContext { width, height, load_factor, field_time, .. } = *ctx;
+        self.flock(config, ctx);
+        simd::integrate(
+            Motion {
+                x: &mut self.x,
+                y: &mut self.y,
+                vx: &mut self.vx,
+                vy: &mut self.vy,
+                prev_x: &mut self.prev_x,
+                prev_y: &mut self.prev_y,
+                life: &mut self.life,
+                drag: &self.drag,
+                lift: &self.lift,
+                decay: &self.decay,
+            },
+            load_factor,
+        );
+        self.grid.invalidate();
+
+        // Mode-specific corrections on top of the shared velocity step
+        for i in 0..self.len() {
+            match self.mode[i] {
+                ParticleMode::Bounce => {
+                    let bounce = config.bounce_restitution;
+                    let size = self.size[i];
+                    if self.x[i] < size || self.x[i] > width - size {
+                        self.vx[i] *= -bounce;
+                        self.x[i] = self.x[i].max(size).min(width - size);
+                    }
+                    if self.y[i] < size || self.y[i] > height - size {
+                        self.vy[i] *= -bounce;
+                        self.y[i] = self.y[i].max(size).min(height - size);
+                    }
+                }
+                ParticleMode::Vortex => {
+                    // Orbit the origin while spiralling inward
+                    self.angle[i] += config.vortex_angular_velocity;
+                    self.radius[i] = (self.radius[i] - config.vortex_spiral_speed).max(0.0);
+                    self.x[i] = self.origin_x[i] + self.angle[i].cos() * self.radius[i];
+                    self.y[i] = self.origin_y[i] + self.angle[i].sin() * self.radius[i];
+                }
+                ParticleMode::Smoke => {
+                    // Ease toward the field velocity so puffs swirl without jitter
+                    let (fx, fy) = noise::curl(self.x[i] * SMOKE_SCALE, self.y[i] * SMOKE_SCALE, field_time);
+                    let strength = config.smoke_curl_strength;
+                    self.vx[i] += (fx * strength - self.vx[i]) * SMOKE_EASE;
+                    self.vy[i] += (fy * strength - SMOKE_RISE - self.vy[i]) * SMOKE_EASE;
+                    self.size[i] += config.smoke_growth;
+                }
+                _ => {}
+            }
+        }
+
+        // Walk backwards so swap_remove only ever moves an already-checked particle
+        for i in (0..self.len()).rev() {
+            let alive = self.life[i] > 0.0
+                && self.x[i] > -50.0
+                && self.x[i] < width + 50.0
+                && self.y[i] > -50.0
+                && self.y[i] < height + 50.0;
+            if !alive {
+                self.swap_remove(i);
+            }
+        }
+    }
+}
+
+// Wire form: one object per particle plus its spawn number, so JSON snapshots
+// stay readable and can't describe mismatched columns
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct RawParticleStore {
+    particles: Vec<Particle>,
+    born: Vec<u64>,
+    next_born: u64,
+}
+
+#[cfg(feature = "serde")]
+impl From<ParticleStore> for RawParticleStore {
+    fn from(store: ParticleStore) -> Self {
+        RawParticleStore { particles: store.iter().collect(), born: store.born, next_born: store.next_born }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawParticleStore> for ParticleStore {
+    type Error = &'static str;
+
+    fn try_from(raw: RawParticleStore) -> Result<Self, Self::Error> {
+        if raw.particles.len() != raw.born.len() {
+            return Err("particle and spawn number counts differ");
+        }
+        // Coefficients are set again by `retune` once the engine config is known
+        let config = EngineConfig::default();
+        let mut store = ParticleStore::default();
+        for (particle, born) in raw.particles.into_iter().zip(raw.born) {
+            store.push_with_born(particle, born, &config);
+        }
+        store.next_born = store.next_born.max(raw.next_born);
+        Ok(store)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_swap_remove_keeps_columns_aligned() {
+        let config = EngineConfig::default();
+        let mut store = ParticleStore::default();
+        for i in 0..4 {
+            let color = Rgba::rgb(i as u8, 0, 0);
+            store.push(Particle::new(i as f64, 0.0, 0.0, 0.0, color, 1.0, ParticleMode::Burst), &config);
+        }
+        store.remove_indices(&mut [0, 2]);
+
+        let xs: Vec<f64> = store.iter().map(|p| p.x()).collect();
+        assert_eq!(xs, [3.0, 1.0]);
+        assert_eq!(store.color(0), Rgba::rgb(3, 0, 0));
+        assert_eq!(store.born(), [3, 1]);
+        assert_eq!(store.next_born, 4);
+    }
+
+    #[test]
+    fn test_compact_colors_drops_unreferenced_entries() {
+        let config = EngineConfig::default();
+        let mut store = ParticleStore::default();
+        for i in 0..6 {
+            let color = Rgba::rgb(i as u8, 0, 0);
+            store.push(Particle::new(i as f64, 0.0, 0.0, 0.0, color, 1.0, ParticleMode::Burst), &config);
+        }
+        store.remove_indices(&mut [0, 2, 3]);
+        assert_eq!(store.colors.len(), 6);
+
+        store.compact_colors();
+        assert_eq!(store.colors.len(), 3);
+        let reds: Vec<u8> = (0..store.len()).map(|i| store.color(i).r).collect();
+        assert_eq!(reds, [5, 1, 4]);
+
+        // A color seen again after compaction gets a fresh entry
+        store.push(Particle::new(0.0, 0.0, 0.0, 0.0, Rgba::rgb(0, 0, 0), 1.0, ParticleMode::Burst), &config);
+        assert_eq!(store.colors.len(), 4);
+        assert_eq!(store.color(3), Rgba::rgb(0, 0, 0));
+    }
+
+    #[test]
+    fn test_update_matches_per_mode_physics() {
+        let config = EngineConfig::default();
+        let mut store = ParticleStore::default();
+        store.push(Particle::new(100.0, 100.0, 2.0, -3.0, Rgba::WHITE, 2.0, ParticleMode::Gravity), &config);
+        store.push(Particle::new(1.0, 100.0, -4.0, 0.0, Rgba::WHITE, 2.0, ParticleMode::Bounce), &config);
+        store.push(Particle::new_vortex(200.0, 200.0, 0.0, 50.0, Rgba::WHITE, 2.0), &config);
+        store.push(Particle::new(150.0, 250.0, 0.5, 0.0, Rgba::WHITE, 2.0, ParticleMode::Smoke), &config);
+        store.update(&config, &step(400.0, 400.0, 3.5));
+
+        let gravity = store.get(0);
+        assert_eq!(gravity.vy, -3.0 * config.friction + config.gravity);
+        assert_eq!((gravity.prev_x, gravity.x), (100.0, 100.0 + 2.0 * config.friction));
+        assert_eq!(gravity.life, 1.0 - config.particle_decay);
+
+        let bounce = store.get(1);
+        assert_eq!(bounce.x, 2.0);
+        assert!(bounce.vx > 0.0);
+
+        let vortex = store.get(2);
+        assert_eq!(vortex.radius, 50.0 - config.vortex_spiral_speed);
+        assert_eq!(vortex.x, 200.0 + config.vortex_angular_velocity.cos() * vortex.radius);
+
+        let smoke = store.get(3);
+        let (fx, _) = noise::curl(smoke.x * SMOKE_SCALE, smoke.y * SMOKE_SCALE, 3.5);
+        let vx = 0.5 * config.friction;
+        assert_eq!(smoke.vx, vx + (fx * config.smoke_curl_strength - vx) * SMOKE_EASE);
+        assert_eq!(smoke.size, 2.0 + config.smoke_growth);
+        assert_eq!(smoke.life, 1.0 - config.smoke_decay);
+    }
+
+    fn step(width: f64, height: f64, field_time: f64) -> StepContext {
+        StepContext { width, height, load_factor: 1.0, field_time, pointer: None }
+    }
+
+    #[test]
+    fn test_flock_steers_toward_neighbours_and_pointer() {
+        let config = EngineConfig::default();
+        let mut store = ParticleStore::default();
+        store.push(Particle::new(100.0, 100.0, 1.0, 0.0, Rgba::WHITE, 2.0, ParticleMode::Boids), &config);
+        store.push(Particle::new(130.0, 100.0, 0.0, 1.0, Rgba::WHITE, 2.0, ParticleMode::Boids), &config);
+        store.push(Particle::new(300.0, 300.0, 2.0, 0.0, Rgba::WHITE, 2.0, ParticleMode::Boids), &config);
+        let ctx = StepContext { pointer: Some(Point { x: 300.0, y: 200.0 }), ..step(400.0, 400.0, 0.0) };
+        store.flock(&config, &ctx);
+
+        // The pair sees each other: they close the gap and `a` picks up `b`'s heading
+        let (a, b) = (store.get(0), store.get(1));
+        assert!(a.vx > 1.0 && b.vx < 0.0);
+        assert!(a.vy > 0.0);
+        // The loner only feels the pointer straight above it
+        let loner = store.get(2);
+        assert_eq!(loner.vx, 2.0);
+        assert_eq!(loner.vy, -config.boid_pointer_pull);
+        for p in store.iter() {
+            let speed = p.vx.hypot(p.vy);
+            assert!(speed >= config.boid_max_speed * 0.5 - 1e-9 && speed <= config.boid_max_speed + 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_update_culls_dead_and_offscreen() {
+        let config = EngineConfig::default();
+        let mut store = ParticleStore::default();
+        let mut dying = Particle::new(10.0, 10.0, 0.0, 0.0, Rgba::WHITE, 1.0, ParticleMode::Burst);
+        dying.life = 0.001;
+        store.push(dying, &config);
+        store.push(Particle::new(10.0, 10.0, 0.0, 0.0, Rgba::WHITE, 1.0, ParticleMode::Burst), &config);
+        store.push(Particle::new(-60.0, 10.0, 0.0, 0.0, Rgba::WHITE, 1.0, ParticleMode::Burst), &config);
+        store.update(&config, &step(100.0, 100.0, 0.0));
+        assert_eq!(store.len(), 1);
+        assert_eq!(store.born(), [1]);
+    }
+}
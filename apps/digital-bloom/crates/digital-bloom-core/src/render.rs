@@ -0,0 +1,349 @@
+//! Headless software rasterizer
+//!
+//! Draws the current scene into an RGBA8 framebuffer the same way the web
+//! canvas renderer does: lightning first, then particles, then vines. Shapes are
+//! anti-aliased by distance to the shape edge, and an optional additive halo
+//! stands in for the canvas `shadowBlur` glow.
+//!
+//! The scene is scaled from the engine's canvas size (see `set_bounds`) to the
+//! framebuffer, so any output resolution works.
+
+use crate::bloom::DigitalBloom;
+use crate::color::Rgba;
+use crate::link::LINK_WIDTH;
+use crate::Point;
+
+/// Most pixels a framebuffer may hold (8192 x 8192, 256 MiB of RGBA8)
+pub const MAX_FRAME_PIXELS: usize = 8192 * 8192;
+
+/// Row-major RGBA8 pixels, straight (non-premultiplied) alpha
+#[derive(Clone, Debug)]
+pub struct Framebuffer {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+    // Scratch space reused by every stroke drawn into this framebuffer
+    strokes: StrokeBuffers,
+}
+
+impl PartialEq for Framebuffer {
+    fn eq(&self, other: &Self) -> bool {
+        (self.width, self.height) == (other.width, other.height) && self.pixels == other.pixels
+    }
+}
+
+/// Points of the polyline being stroked and its per-pixel coverage
+#[derive(Clone, Debug, Default)]
+struct StrokeBuffers {
+    points: Vec<Point>,
+    coverage: Vec<f64>,
+    halo: Vec<f64>,
+}
+
+impl Framebuffer {
+    /// Transparent black framebuffer
+    ///
+    /// Panics if `width * height` is above `MAX_FRAME_PIXELS`; use
+    /// `Framebuffer::fits` to check untrusted sizes first.
+    pub fn new(width: usize, height: usize) -> Self {
+        assert!(Framebuffer::fits(width, height), "framebuffer of {width}x{height} is too large");
+        Framebuffer { width, height, pixels: vec![0; width * height * 4], strokes: StrokeBuffers::default() }
+    }
+
+    /// Whether a `width` x `height` framebuffer stays within `MAX_FRAME_PIXELS`
+    pub fn fits(width: usize, height: usize) -> bool {
+        width.checked_mul(height).is_some_and(|pixels| pixels <= MAX_FRAME_PIXELS)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub fn into_pixels(self) -> Vec<u8> {
+        self.pixels
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> Rgba {
+        let i = (y * self.width + x) * 4;
+        Rgba::new(self.pixels[i], self.pixels[i + 1], self.pixels[i + 2], self.pixels[i + 3])
+    }
+
+    pub fn fill(&mut self, color: Rgba) {
+        for px in self.pixels.chunks_exact_mut(4) {
+            px.copy_from_slice(&[color.r, color.g, color.b, color.a]);
+        }
+    }
+
+    /// Composite `color` at `alpha` coverage with the source-over operator
+    fn blend_over(&mut self, x: usize, y: usize, color: Rgba, alpha: f64) {
+        let i = (y * self.width + x) * 4;
+        let px = &mut self.pixels[i..i + 4];
+        let src_a = alpha * color.a as f64 / 255.0;
+        let dst_a = px[3] as f64 / 255.0;
+        let out_a = src_a + dst_a * (1.0 - src_a);
+        if out_a <= 0.0 {
+            return;
+        }
+        for (c, src) in px[..3].iter_mut().zip([color.r, color.g, color.b]) {
+            let mixed = (src as f64 * src_a + *c as f64 * dst_a * (1.0 - src_a)) / out_a;
+            *c = mixed.round() as u8;
+        }
+        px[3] = (out_a * 255.0).round() as u8;
+    }
+
+    /// Add `color` scaled by `alpha`, saturating, for glow halos
+    fn blend_add(&mut self, x: usize, y: usize, color: Rgba, alpha: f64) {
+        let i = (y * self.width + x) * 4;
+        let px = &mut self.pixels[i..i + 4];
+        let src_a = alpha * color.a as f64 / 255.0;
+        for (c, src) in px[..3].iter_mut().zip([color.r, color.g, color.b]) {
+            *c = (*c as f64 + src as f64 * src_a).min(255.0).round() as u8;
+        }
+        px[3] = (px[3] as f64 + src_a * 255.0).min(255.0).round() as u8;
+    }
+}
+
+/// Look of a rendered frame
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenderOptions {
+    /// Fill color before drawing; `None` draws over the existing pixels
+    pub background: Option<Rgba>,
+    /// Glow strength multiplier; 0.0 disables the additive halos
+    pub glow: f64,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            background: Some(Rgba::BACKDROP),
+            glow: 1.0,
+        }
+    }
+}
+
+impl DigitalBloom {
+    /// Rasterize the current scene into a new `width` x `height` framebuffer
+    ///
+    /// Returns `None` if the size is above `MAX_FRAME_PIXELS`.
+    pub fn render(&self, width: usize, height: usize, options: &RenderOptions) -> Option<Framebuffer> {
+        if !Framebuffer::fits(width, height) {
+            return None;
+        }
+        let mut framebuffer = Framebuffer::new(width, height);
+        self.render_into(&mut framebuffer, options);
+        Some(framebuffer)
+    }
+
+    /// Rasterize the current scene into an existing framebuffer
+    pub fn render_into(&self, framebuffer: &mut Framebuffer, options: &RenderOptions) {
+        if let Some(background) = options.background {
+            framebuffer.fill(background);
+        }
+        if self.width <= 0.0 || self.height <= 0.0 {
+            return;
+        }
+
+        let scale_x = framebuffer.width as f64 / self.width;
+        let scale_y = framebuffer.height as f64 / self.height;
+        let scale = (scale_x + scale_y) * 0.5;
+        let to_pixels = |p: &Point| Point { x: p.x * scale_x, y: p.y * scale_y };
+        let mut strokes = std::mem::take(&mut framebuffer.strokes);
+
+        for lightning in &self.lightnings {
+            strokes.set_points(lightning.segments.iter().map(to_pixels));
+            let width = lightning.line_width * lightning.life * scale;
+            let glow = 10.0 * lightning.life * scale * options.glow;
+            stroke_polyline(framebuffer, &mut strokes, width, lightning.color, lightning.life, glow);
+            for branch in &lightning.branches {
+                strokes.set_points(branch.iter().map(to_pixels));
+                stroke_polyline(framebuffer, &mut strokes, width * 0.6, lightning.color, lightning.life, 0.0);
+            }
+        }
+
+        // Links sit behind the stars they join
+        for link in &self.links {
+            strokes.set_points([to_pixels(&link.from), to_pixels(&link.to)]);
+            stroke_polyline(framebuffer, &mut strokes, LINK_WIDTH * scale, link.color, link.alpha, 0.0);
+        }
+
+        for particle in self.particles.iter() {
+            let center = to_pixels(&Point { x: particle.x, y: particle.y });
+            let radius = particle.size * particle.life * scale;
+            fill_circle(framebuffer, center, radius, particle.color, particle.life, radius * options.glow);
+        }
+
+        for vine in self.vines.iter().chain(&self.grown_vines) {
+            strokes.set_points(vine.points().iter().map(to_pixels));
+            stroke_polyline(framebuffer, &mut strokes, vine.line_width * scale, vine.color, 1.0, 0.0);
+        }
+        framebuffer.strokes = strokes;
+    }
+}
+
+impl StrokeBuffers {
+    fn set_points(&mut self, points: impl IntoIterator<Item = Point>) {
+        self.points.clear();
+        self.points.extend(points);
+    }
+}
+
+/// Pixel range covering [min, max] expanded by `pad`, clipped to `0..limit`
+fn pixel_span(min: f64, max: f64, pad: f64, limit: usize) -> std::ops::Range<usize> {
+    let start = (min - pad).floor().max(0.0) as usize;
+    let end = ((max + pad).ceil().max(0.0) as usize).min(limit);
+    start.min(end)..end
+}
+
+/// Quadratic halo falloff from the shape edge out to `radius`
+fn glow_falloff(distance_outside: f64, radius: f64) -> f64 {
+    if radius <= 0.0 {
+        return 0.0;
+    }
+    let t = (1.0 - distance_outside / radius).clamp(0.0, 1.0);
+    t * t * 0.5
+}
+
+fn fill_circle(fb: &mut Framebuffer, center: Point, radius: f64, color: Rgba, alpha: f64, glow: f64) {
+    if radius <= 0.0 || alpha <= 0.0 {
+        return;
+    }
+    let pad = radius + glow + 1.0;
+    for y in pixel_span(center.y, center.y, pad, fb.height) {
+        for x in pixel_span(center.x, center.x, pad, fb.width) {
+            let distance = (x as f64 + 0.5 - center.x).hypot(y as f64 + 0.5 - center.y);
+            let halo = glow_falloff(distance - radius, glow);
+            if halo > 0.0 {
+                fb.blend_add(x, y, color, halo * alpha);
+            }
+            let coverage = (radius + 0.5 - distance).clamp(0.0, 1.0);
+            if coverage > 0.0 {
+                fb.blend_over(x, y, color, coverage * alpha);
+            }
+        }
+    }
+}
+
+/// Stroke `strokes.points` as a polyline with round joins and caps
+///
+/// Coverage is the max over all segments, so joints aren't drawn twice.
+fn stroke_polyline(fb: &mut Framebuffer, strokes: &mut StrokeBuffers, width: f64, color: Rgba, alpha: f64, glow: f64) {
+    let StrokeBuffers { points, coverage, halo } = strokes;
+    let points = points.as_slice();
+    if points.len() < 2 || width <= 0.0 || alpha <= 0.0 {
+        return;
+    }
+    let half = width * 0.5;
+    let pad = half + glow + 1.0;
+
+    let (mut min, mut max) = (points[0], points[0]);
+    for p in points {
+        min = Point { x: min.x.min(p.x), y: min.y.min(p.y) };
+        max = Point { x: max.x.max(p.x), y: max.y.max(p.y) };
+    }
+    let xs = pixel_span(min.x, max.x, pad, fb.width);
+    let ys = pixel_span(min.y, max.y, pad, fb.height);
+    if xs.is_empty() || ys.is_empty() {
+        return;
+    }
+
+    let stride = xs.len();
+    coverage.clear();
+    coverage.resize(stride * ys.len(), 0.0);
+    halo.clear();
+    halo.resize(if glow > 0.0 { coverage.len() } else { 0 }, 0.0);
+
+    for segment in points.windows(2) {
+        let (a, b) = (segment[0], segment[1]);
+        let seg_xs = pixel_span(a.x.min(b.x), a.x.max(b.x), pad, fb.width);
+        let seg_ys = pixel_span(a.y.min(b.y), a.y.max(b.y), pad, fb.height);
+        for y in seg_ys {
+            for x in seg_xs.clone() {
+                let distance = distance_to_segment(Point { x: x as f64 + 0.5, y: y as f64 + 0.5 }, a, b);
+                let i = (y - ys.start) * stride + (x - xs.start);
+                coverage[i] = coverage[i].max((half + 0.5 - distance).clamp(0.0, 1.0));
+                if glow > 0.0 {
+                    halo[i] = halo[i].max(glow_falloff(distance - half, glow));
+                }
+            }
+        }
+    }
+
+    for (row, y) in ys.enumerate() {
+        for (col, x) in xs.clone().enumerate() {
+            let i = row * stride + col;
+            if glow > 0.0 && halo[i] > 0.0 {
+                fb.blend_add(x, y, color, halo[i] * alpha);
+            }
+            if coverage[i] > 0.0 {
+                fb.blend_over(x, y, color, coverage[i] * alpha);
+            }
+        }
+    }
+}
+
+fn distance_to_segment(p: Point, a: Point, b: Point) -> f64 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let length_sq = dx * dx + dy * dy;
+    let t = if length_sq > 0.0 { (((p.x - a.x) * dx + (p.y - a.y) * dy) / length_sq).clamp(0.0, 1.0) } else { 0.0 };
+    (p.x - (a.x + t * dx)).hypot(p.y - (a.y + t * dy))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::palette::Palette;
+
+    #[test]
+    fn test_empty_scene_is_background() {
+        let bloom = DigitalBloom::with_seed(1);
+        let options = RenderOptions { background: Some(Rgba::rgb(1, 2, 3)), glow: 1.0 };
+        let frame = bloom.render(8, 4, &options).unwrap();
+        assert_eq!(frame.pixels().len(), 8 * 4 * 4);
+        assert!(frame.pixels().chunks_exact(4).all(|px| px == [1, 2, 3, 255]));
+
+        assert!(bloom.render(usize::MAX, usize::MAX, &options).is_none());
+        assert!(bloom.render(MAX_FRAME_PIXELS, 2, &options).is_none());
+    }
+
+    #[test]
+    fn test_draws_scaled_particles_and_vines() {
+        let mut bloom = DigitalBloom::with_seed(2);
+        bloom.set_palette(Palette::new(vec![Rgba::rgb(255, 0, 0)]).unwrap());
+        bloom.set_bounds(100.0, 100.0);
+        bloom.create_particles_bounce(20.0, 80.0, 1, 20.0);
+        bloom.create_vine(50.0, 50.0, 6.0);
+        for _ in 0..20 {
+            bloom.update(100.0, 100.0);
+        }
+
+        let options = RenderOptions { background: Some(Rgba::rgb(0, 0, 0)), glow: 0.0 };
+        let frame = bloom.render(200, 200, &options).unwrap();
+        let p = bloom.particles().get(0);
+        let center = frame.pixel((p.x() * 2.0) as usize, (p.y() * 2.0) as usize);
+        assert!(center.r > 128 && center.g == 0);
+
+        let tip = bloom.vines_slice()[0].points()[10];
+        assert!(frame.pixel((tip.x * 2.0) as usize, (tip.y * 2.0) as usize).r > 0);
+        assert_eq!(frame.pixel(199, 0), Rgba::rgb(0, 0, 0));
+    }
+
+    #[test]
+    fn test_glow_brightens_surroundings() {
+        let mut bloom = DigitalBloom::with_seed(3);
+        bloom.set_bounds(100.0, 100.0);
+        bloom.create_lightning(50.0, 10.0, 100.0, 100.0);
+
+        let plain = bloom.render(100, 100, &RenderOptions { glow: 0.0, ..RenderOptions::default() }).unwrap();
+        let glowing = bloom.render(100, 100, &RenderOptions::default()).unwrap();
+        let brightness = |frame: &Framebuffer| frame.pixels().iter().map(|&c| c as u64).sum::<u64>();
+        assert!(brightness(&glowing) > brightness(&plain));
+    }
+}
@@ -0,0 +1,364 @@
+//! Input event recording and deterministic replay
+//!
+//! A `Recording` captures the engine state when recording started plus every
+//! call made on the engine afterwards. Replaying it on the captured state
+//! reproduces the exact same animation, so a recording attached to a bug report
+//! (or checked in as a test fixture) replays identically on any platform.
+//!
+//! The binary format is little-endian and starts with the magic bytes `DBRP`
+//! followed by a u16 version, the starting snapshot and the event list.
+
+use crate::bloom::{DigitalBloom, MAX_SPAWN_COUNT};
+use crate::budget::BudgetPolicy;
+use crate::config::EngineConfig;
+use crate::mode::ParticleMode;
+use crate::palette::Palette;
+use crate::snapshot::{read_config, read_palette, write_config, write_palette, Reader, SnapshotError, Writer};
+
+const MAGIC: &[u8; 4] = b"DBRP";
+
+/// Current recording format version, bumped whenever the layout changes
+pub const RECORDING_VERSION: u16 = 5;
+
+/// One recorded call on the engine, with its arguments
+#[derive(Clone, Debug, PartialEq)]
+pub enum InputEvent {
+    Vine { x: f64, y: f64, size: f64 },
+    Particles { mode: ParticleMode, x: f64, y: f64, count: usize, size: f64 },
+    Lightning { x: f64, y: f64, width: f64, height: f64 },
+    Update { width: f64, height: f64 },
+    UpdateDt { dt_seconds: f64 },
+    SetBounds { width: f64, height: f64 },
+    Clear,
+    SetConfig(EngineConfig),
+    SetPalette(Palette),
+    SetSeed(u64),
+    SetBudgetPolicy(BudgetPolicy),
+    /// Engine state was replaced by this binary snapshot
+    Restore(Vec<u8>),
+    SetPointer { x: f64, y: f64 },
+    ClearPointer,
+}
+
+/// Starting state plus the log of calls made since `start_recording`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recording {
+    start: Vec<u8>,
+    events: Vec<InputEvent>,
+}
+
+impl Recording {
+    pub(crate) fn new(start: Vec<u8>) -> Self {
+        Recording { start, events: Vec::new() }
+    }
+
+    pub(crate) fn push(&mut self, event: InputEvent) {
+        self.events.push(event);
+    }
+
+    /// Recorded calls, oldest first
+    pub fn events(&self) -> &[InputEvent] {
+        &self.events
+    }
+
+    /// Rebuild the starting state and feed every recorded call back into it
+    pub fn replay(&self) -> Result<DigitalBloom, SnapshotError> {
+        let mut bloom = self.start()?;
+        for event in &self.events {
+            bloom.apply(event)?;
+        }
+        Ok(bloom)
+    }
+
+    /// Replay the recording and keep recording on top of it
+    ///
+    /// New calls on the returned engine are appended to this same log, so a
+    /// session can be picked up where a saved recording left off.
+    pub fn resume(self) -> Result<DigitalBloom, SnapshotError> {
+        let mut bloom = self.replay()?;
+        bloom.recording = Some(self);
+        Ok(bloom)
+    }
+
+    /// Engine state when recording started
+    pub(crate) fn start(&self) -> Result<DigitalBloom, SnapshotError> {
+        DigitalBloom::from_snapshot(&self.start)
+    }
+
+    /// Serialize the recording to the `DBRP` binary format
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer(Vec::new());
+        w.0.extend_from_slice(MAGIC);
+        w.u16(RECORDING_VERSION);
+        w.len(self.start.len());
+        w.0.extend_from_slice(&self.start);
+        w.len(self.events.len());
+        for event in &self.events {
+            write_event(&mut w, event);
+        }
+        w.0
+    }
+
+    /// Parse bytes produced by `to_bytes`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        let mut r = Reader(bytes);
+        if r.take(4)? != MAGIC {
+            return Err(SnapshotError::BadMagic);
+        }
+        let version = r.u16()?;
+        if version != RECORDING_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+
+        let start = read_bytes(&mut r)?;
+        // Validate up front so a bad file fails here rather than mid-replay
+        DigitalBloom::from_snapshot(&start)?;
+        let events = r.list(read_event)?;
+
+        if !r.0.is_empty() {
+            return Err(SnapshotError::Invalid("trailing data"));
+        }
+        Ok(Recording { start, events })
+    }
+}
+
+impl DigitalBloom {
+    /// Start logging every call made on this engine, discarding any previous log
+    pub fn start_recording(&mut self) {
+        self.recording = Some(Recording::new(self.snapshot()));
+    }
+
+    /// Stop logging and hand back the recording, if one was running
+    pub fn stop_recording(&mut self) -> Option<Recording> {
+        self.recording.take()
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Recording in progress, without stopping it
+    pub fn recording(&self) -> Option<&Recording> {
+        self.recording.as_ref()
+    }
+
+    pub(crate) fn record(&mut self, event: InputEvent) {
+        if let Some(recording) = &mut self.recording {
+            recording.push(event);
+        }
+    }
+
+    /// Perform the call an event describes
+    pub(crate) fn apply(&mut self, event: &InputEvent) -> Result<(), SnapshotError> {
+        match event {
+            InputEvent::Vine { x, y, size } => self.create_vine(*x, *y, *size),
+            InputEvent::Particles { mode, x, y, count, size } => self.create(*mode, *x, *y, *count, *size),
+            InputEvent::Lightning { x, y, width, height } => self.create_lightning(*x, *y, *width, *height),
+            InputEvent::Update { width, height } => self.update(*width, *height),
+            InputEvent::UpdateDt { dt_seconds } => {
+                self.update_dt(*dt_seconds);
+            }
+            InputEvent::SetBounds { width, height } => self.set_bounds(*width, *height),
+            InputEvent::Clear => self.clear(),
+            InputEvent::SetConfig(config) => self.set_config(config.clone()),
+            InputEvent::SetPalette(palette) => self.set_palette(palette.clone()),
+            InputEvent::SetSeed(seed) => self.set_seed(*seed),
+            InputEvent::SetBudgetPolicy(policy) => self.set_budget_policy(*policy),
+            InputEvent::Restore(bytes) => self.restore(bytes)?,
+            InputEvent::SetPointer { x, y } => self.set_pointer(*x, *y),
+            InputEvent::ClearPointer => self.clear_pointer(),
+        }
+        Ok(())
+    }
+}
+
+// ==================== ENCODING ====================
+
+fn write_event(w: &mut Writer, event: &InputEvent) {
+    match event {
+        InputEvent::Vine { x, y, size } => {
+            w.u8(0);
+            for v in [*x, *y, *size] {
+                w.f64(v);
+            }
+        }
+        InputEvent::Particles { mode, x, y, count, size } => {
+            w.u8(1);
+            w.u8(*mode as u8);
+            w.f64(*x);
+            w.f64(*y);
+            w.u64(*count as u64);
+            w.f64(*size);
+        }
+        InputEvent::Lightning { x, y, width, height } => {
+            w.u8(2);
+            for v in [*x, *y, *width, *height] {
+                w.f64(v);
+            }
+        }
+        InputEvent::Update { width, height } => {
+            w.u8(3);
+            w.f64(*width);
+            w.f64(*height);
+        }
+        InputEvent::UpdateDt { dt_seconds } => {
+            w.u8(4);
+            w.f64(*dt_seconds);
+        }
+        InputEvent::SetBounds { width, height } => {
+            w.u8(5);
+            w.f64(*width);
+            w.f64(*height);
+        }
+        InputEvent::Clear => w.u8(6),
+        InputEvent::SetConfig(config) => {
+            w.u8(7);
+            write_config(w, config);
+        }
+        InputEvent::SetPalette(palette) => {
+            w.u8(8);
+            write_palette(w, palette);
+        }
+        InputEvent::SetSeed(seed) => {
+            w.u8(9);
+            w.u64(*seed);
+        }
+        InputEvent::SetBudgetPolicy(policy) => {
+            let (kind, max) = policy.to_raw();
+            w.u8(10);
+            w.u8(kind);
+            w.u64(max as u64);
+        }
+        InputEvent::Restore(bytes) => {
+            w.u8(11);
+            w.len(bytes.len());
+            w.0.extend_from_slice(bytes);
+        }
+        InputEvent::SetPointer { x, y } => {
+            w.u8(12);
+            w.f64(*x);
+            w.f64(*y);
+        }
+        InputEvent::ClearPointer => w.u8(13),
+    }
+}
+
+// ==================== DECODING ====================
+
+fn read_bytes(r: &mut Reader) -> Result<Vec<u8>, SnapshotError> {
+    let len = r.u32()? as usize;
+    Ok(r.take(len)?.to_vec())
+}
+
+fn read_event(r: &mut Reader) -> Result<InputEvent, SnapshotError> {
+    Ok(match r.u8()? {
+        0 => InputEvent::Vine { x: r.f64()?, y: r.f64()?, size: r.f64()? },
+        1 => InputEvent::Particles {
+            mode: ParticleMode::try_from(r.u8()?).map_err(|_| SnapshotError::Invalid("particle mode"))?,
+            x: r.f64()?,
+            y: r.f64()?,
+            count: match r.usize()? {
+                count if count <= MAX_SPAWN_COUNT => count,
+                _ => return Err(SnapshotError::Invalid("particle count")),
+            },
+            size: r.f64()?,
+        },
+        2 => InputEvent::Lightning { x: r.f64()?, y: r.f64()?, width: r.f64()?, height: r.f64()? },
+        3 => InputEvent::Update { width: r.f64()?, height: r.f64()? },
+        4 => InputEvent::UpdateDt { dt_seconds: r.f64()? },
+        5 => InputEvent::SetBounds { width: r.f64()?, height: r.f64()? },
+        6 => InputEvent::Clear,
+        7 => InputEvent::SetConfig(read_config(r)?),
+        8 => InputEvent::SetPalette(read_palette(r)?),
+        9 => InputEvent::SetSeed(r.u64()?),
+        10 => {
+            let kind = r.u8()?;
+            InputEvent::SetBudgetPolicy(BudgetPolicy::from_raw(kind, r.usize()?).ok_or(SnapshotError::Invalid("budget policy"))?)
+        }
+        11 => {
+            let bytes = read_bytes(r)?;
+            DigitalBloom::from_snapshot(&bytes)?;
+            InputEvent::Restore(bytes)
+        }
+        12 => InputEvent::SetPointer { x: r.f64()?, y: r.f64()? },
+        13 => InputEvent::ClearPointer,
+        _ => return Err(SnapshotError::Invalid("event")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draw_scene(bloom: &mut DigitalBloom) {
+        bloom.create(ParticleMode::Burst, 100.0, 100.0, 20, 8.0);
+        bloom.create(ParticleMode::Vine, 200.0, 200.0, 1, 4.0);
+        bloom.update(300.0, 300.0);
+        bloom.create(ParticleMode::Lightning, 150.0, 50.0, 1, 4.0);
+        bloom.set_palette(Palette::builtin("ocean").unwrap());
+        bloom.set_budget_policy(BudgetPolicy::RandomCull { max: 25 });
+        bloom.create(ParticleMode::Vortex, 150.0, 150.0, 10, 6.0);
+        for _ in 0..5 {
+            bloom.update_dt(1.0 / 45.0);
+        }
+        bloom.clear();
+        bloom.set_pointer(250.0, 40.0);
+        bloom.create(ParticleMode::Boids, 150.0, 150.0, 6, 4.0);
+        bloom.update(300.0, 300.0);
+        bloom.clear_pointer();
+        bloom.create(ParticleMode::Gravity, 50.0, 50.0, 5, 4.0);
+        bloom.update(300.0, 300.0);
+    }
+
+    #[test]
+    fn test_replay_reproduces_animation() {
+        let mut bloom = DigitalBloom::with_seed(11);
+        bloom.create(ParticleMode::Constellation, 20.0, 20.0, 5, 4.0);
+        bloom.start_recording();
+        draw_scene(&mut bloom);
+        let recording = bloom.stop_recording().unwrap();
+
+        assert!(!bloom.is_recording());
+        assert_eq!(recording.replay().unwrap().snapshot(), bloom.snapshot());
+
+        let mut resumed = recording.clone().resume().unwrap();
+        resumed.update(300.0, 300.0);
+        bloom.update(300.0, 300.0);
+        let extended = resumed.stop_recording().unwrap();
+        assert_eq!(extended.events().len(), recording.events().len() + 1);
+        assert_eq!(extended.replay().unwrap().snapshot(), bloom.snapshot());
+    }
+
+    #[test]
+    fn test_recording_round_trips_through_bytes() {
+        let mut bloom = DigitalBloom::with_seed(5);
+        bloom.start_recording();
+        draw_scene(&mut bloom);
+        let snapshot = bloom.snapshot();
+        bloom.create(ParticleMode::Bounce, 10.0, 10.0, 3, 4.0);
+        bloom.restore(&snapshot).unwrap();
+        bloom.update(300.0, 300.0);
+
+        let recording = Recording::from_bytes(&bloom.recording().unwrap().to_bytes()).unwrap();
+        assert_eq!(&recording, bloom.recording().unwrap());
+        assert_eq!(recording.replay().unwrap().snapshot(), bloom.snapshot());
+
+        let bytes = recording.to_bytes();
+        assert_eq!(Recording::from_bytes(&bytes[..bytes.len() - 1]), Err(SnapshotError::Truncated));
+        assert_eq!(Recording::from_bytes(b"DBLM\x01\x00"), Err(SnapshotError::BadMagic));
+    }
+
+    #[test]
+    fn test_spawn_counts_are_bounded() {
+        let mut bloom = DigitalBloom::with_seed(6);
+        bloom.start_recording();
+        bloom.create(ParticleMode::Burst, 10.0, 10.0, usize::MAX, 4.0);
+        let recording = bloom.recording().unwrap();
+        assert!(matches!(recording.events[0], InputEvent::Particles { count: MAX_SPAWN_COUNT, .. }));
+
+        // A hand-edited file can't smuggle in a larger count
+        let mut hostile = recording.clone();
+        hostile.events[0] = InputEvent::Particles { mode: ParticleMode::Burst, x: 0.0, y: 0.0, count: usize::MAX, size: 4.0 };
+        assert_eq!(Recording::from_bytes(&hostile.to_bytes()), Err(SnapshotError::Invalid("particle count")));
+    }
+}
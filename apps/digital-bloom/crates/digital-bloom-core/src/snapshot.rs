@@ -0,0 +1,563 @@
+//! Versioned save/restore of the complete engine state
+//!
+//! The binary format is little-endian and starts with the magic bytes `DBLM`
+//! followed by a u16 version. Floats are stored at full precision so a
+//! restored engine continues the exact same animation as the original.
+
+use std::fmt;
+
+use crate::bloom::DigitalBloom;
+use crate::budget::BudgetPolicy;
+use crate::color::Rgba;
+use crate::config::EngineConfig;
+use crate::lightning::Lightning;
+use crate::mode::ParticleMode;
+use crate::palette::Palette;
+use crate::particle::{Charge, Particle};
+use crate::replay::InputEvent;
+use crate::rng::Rng;
+use crate::vine::Vine;
+use crate::Point;
+
+const MAGIC: &[u8; 4] = b"DBLM";
+
+/// Current snapshot format version, bumped whenever the layout changes
+pub const SNAPSHOT_VERSION: u16 = 8;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SnapshotError {
+    /// Data doesn't start with the snapshot magic bytes
+    BadMagic,
+    /// Snapshot was written by a newer (or unknown) format version
+    UnsupportedVersion(u16),
+    /// Data ended before the snapshot was complete
+    Truncated,
+    /// A field holds a value the engine can't represent
+    Invalid(&'static str),
+    /// JSON snapshot could not be parsed
+    Json(String),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::BadMagic => write!(f, "not a Digital Bloom snapshot"),
+            SnapshotError::UnsupportedVersion(v) => write!(f, "unsupported snapshot version {v}"),
+            SnapshotError::Truncated => write!(f, "snapshot is truncated"),
+            SnapshotError::Invalid(what) => write!(f, "snapshot has invalid {what}"),
+            SnapshotError::Json(err) => write!(f, "invalid JSON snapshot: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl DigitalBloom {
+    /// Serialize the complete engine state (scene, RNG, config, palette) to bytes
+    pub fn snapshot(&self) -> Vec<u8> {
+        let mut w = Writer(Vec::new());
+        w.0.extend_from_slice(MAGIC);
+        w.u16(SNAPSHOT_VERSION);
+
+        w.u64(self.seed);
+        w.u64(self.rng.state());
+        w.f64(self.width);
+        w.f64(self.height);
+        w.f64(self.accumulator);
+        w.u64(self.steps);
+        w.bool(self.pointer.is_some());
+        let pointer = self.pointer.unwrap_or(Point { x: 0.0, y: 0.0 });
+        w.f64(pointer.x);
+        w.f64(pointer.y);
+        let (budget_kind, budget_max) = self.budget.to_raw();
+        w.u8(budget_kind);
+        w.u64(budget_max as u64);
+        w.u64(self.max_lightnings as u64);
+
+        write_config(&mut w, &self.config);
+        write_palette(&mut w, &self.palette);
+
+        w.u64(self.particles.next_born);
+        w.len(self.particles.len());
+        for (particle, &born) in self.particles.iter().zip(self.particles.born()) {
+            write_particle(&mut w, &particle);
+            w.u64(born);
+        }
+        for vines in [&self.vines, &self.grown_vines] {
+            w.len(vines.len());
+            for vine in vines.iter() {
+                write_vine(&mut w, vine);
+            }
+        }
+        w.len(self.lightnings.len());
+        for lightning in &self.lightnings {
+            write_lightning(&mut w, lightning);
+        }
+
+        w.0
+    }
+
+    /// Rebuild an engine from bytes produced by `snapshot`
+    pub fn from_snapshot(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        let mut r = Reader(bytes);
+        if r.take(4)? != MAGIC {
+            return Err(SnapshotError::BadMagic);
+        }
+        let version = r.u16()?;
+        if version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+
+        let mut bloom = DigitalBloom::with_seed(r.u64()?);
+        bloom.rng = Rng::from_state(r.u64()?);
+        bloom.width = r.f64()?;
+        bloom.height = r.f64()?;
+        bloom.accumulator = r.f64()?;
+        bloom.steps = r.u64()?;
+        let has_pointer = r.bool()?;
+        let pointer = Point { x: r.f64()?, y: r.f64()? };
+        bloom.pointer = has_pointer.then_some(pointer);
+        let budget_kind = r.u8()?;
+        bloom.budget = BudgetPolicy::from_raw(budget_kind, r.usize()?).ok_or(SnapshotError::Invalid("budget policy"))?;
+        bloom.max_lightnings = r.usize()?;
+
+        bloom.config = read_config(&mut r)?.sanitized();
+        bloom.palette = read_palette(&mut r)?;
+
+        let next_born = r.u64()?;
+        for (particle, born) in r.list(|r| Ok((read_particle(r)?, r.u64()?)))? {
+            bloom.particles.push_with_born(particle, born, &bloom.config);
+        }
+        bloom.particles.next_born = bloom.particles.next_born.max(next_born);
+        bloom.vines = r.list(read_vine)?;
+        bloom.grown_vines = r.list(read_vine)?;
+        bloom.lightnings = r.list(read_lightning)?;
+
+        if !r.0.is_empty() {
+            return Err(SnapshotError::Invalid("trailing data"));
+        }
+        bloom.relink();
+        Ok(bloom)
+    }
+
+    /// Replace this engine's state with a snapshot; on error the engine is unchanged
+    ///
+    /// A recording in progress keeps running and logs the restore.
+    pub fn restore(&mut self, bytes: &[u8]) -> Result<(), SnapshotError> {
+        let restored = DigitalBloom::from_snapshot(bytes)?;
+        self.replace_state(restored, || bytes.to_vec());
+        Ok(())
+    }
+
+    /// Serialize the complete engine state as human-readable JSON
+    #[cfg(feature = "json")]
+    pub fn snapshot_json(&self) -> String {
+        let snapshot = JsonSnapshotRef { version: SNAPSHOT_VERSION, engine: self };
+        serde_json::to_string(&snapshot).expect("engine state is always serializable")
+    }
+
+    /// Rebuild an engine from `snapshot_json` output
+    #[cfg(feature = "json")]
+    pub fn from_snapshot_json(json: &str) -> Result<Self, SnapshotError> {
+        let snapshot: JsonSnapshot = serde_json::from_str(json).map_err(|e| SnapshotError::Json(e.to_string()))?;
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(snapshot.version));
+        }
+        let mut bloom = snapshot.engine;
+        bloom.config = bloom.config.sanitized();
+        bloom.particles.retune(&bloom.config);
+        bloom.relink();
+        Ok(bloom)
+    }
+
+    /// Replace this engine's state with a JSON snapshot; on error the engine is unchanged
+    #[cfg(feature = "json")]
+    pub fn restore_json(&mut self, json: &str) -> Result<(), SnapshotError> {
+        let restored = DigitalBloom::from_snapshot_json(json)?;
+        let snapshot = restored.snapshot();
+        self.replace_state(restored, || snapshot);
+        Ok(())
+    }
+
+    /// Swap in restored state, carrying over any recording in progress
+    fn replace_state(&mut self, restored: DigitalBloom, snapshot: impl FnOnce() -> Vec<u8>) {
+        let recording = self.recording.take();
+        let vine_pool = std::mem::take(&mut self.vine_pool);
+        *self = restored;
+        self.recording = recording;
+        self.vine_pool = vine_pool;
+        if self.is_recording() {
+            self.record(InputEvent::Restore(snapshot()));
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+#[derive(serde::Serialize)]
+struct JsonSnapshotRef<'a> {
+    version: u16,
+    engine: &'a DigitalBloom,
+}
+
+#[cfg(feature = "json")]
+#[derive(serde::Deserialize)]
+struct JsonSnapshot {
+    version: u16,
+    engine: DigitalBloom,
+}
+
+// ==================== ENCODING ====================
+
+pub(crate) struct Writer(pub(crate) Vec<u8>);
+
+impl Writer {
+    pub(crate) fn u8(&mut self, v: u8) {
+        self.0.push(v);
+    }
+
+    pub(crate) fn bool(&mut self, v: bool) {
+        self.u8(v as u8);
+    }
+
+    pub(crate) fn u16(&mut self, v: u16) {
+        self.0.extend_from_slice(&v.to_le_bytes());
+    }
+
+    pub(crate) fn u32(&mut self, v: u32) {
+        self.0.extend_from_slice(&v.to_le_bytes());
+    }
+
+    pub(crate) fn u64(&mut self, v: u64) {
+        self.0.extend_from_slice(&v.to_le_bytes());
+    }
+
+    pub(crate) fn f64(&mut self, v: f64) {
+        self.0.extend_from_slice(&v.to_le_bytes());
+    }
+
+    pub(crate) fn len(&mut self, len: usize) {
+        self.u32(len as u32);
+    }
+
+    pub(crate) fn color(&mut self, c: Rgba) {
+        self.0.extend_from_slice(&[c.r, c.g, c.b, c.a]);
+    }
+
+    pub(crate) fn points(&mut self, points: &[Point]) {
+        self.len(points.len());
+        for p in points {
+            self.f64(p.x);
+            self.f64(p.y);
+        }
+    }
+}
+
+pub(crate) fn write_config(w: &mut Writer, c: &EngineConfig) {
+    for v in [
+        c.friction,
+        c.gravity,
+        c.bounce_restitution,
+        c.particle_decay,
+        c.constellation_decay,
+        c.vortex_decay,
+        c.lightning_decay,
+        c.vortex_angular_velocity,
+        c.vortex_spiral_speed,
+    ] {
+        w.f64(v);
+    }
+    w.u32(c.lightning_segments);
+    for v in [
+        c.lightning_jitter,
+        c.lightning_branch_jitter,
+        c.vine_max_length,
+        c.vine_min_length,
+        c.smoke_decay,
+        c.smoke_growth,
+        c.smoke_curl_strength,
+        c.boid_decay,
+        c.boid_view_radius,
+        c.boid_separation,
+        c.boid_alignment,
+        c.boid_cohesion,
+        c.boid_max_speed,
+        c.boid_pointer_pull,
+        c.constellation_link_distance,
+    ] {
+        w.f64(v);
+    }
+}
+
+pub(crate) fn write_palette(w: &mut Writer, palette: &Palette) {
+    w.len(palette.colors().len());
+    for color in palette.colors() {
+        w.color(*color);
+    }
+    w.bool(palette.weights().is_some());
+    for weight in palette.weights().unwrap_or_default() {
+        w.f64(*weight);
+    }
+}
+
+fn write_particle(w: &mut Writer, p: &Particle) {
+    for v in [p.x, p.y, p.vx, p.vy, p.prev_x, p.prev_y] {
+        w.f64(v);
+    }
+    w.color(p.color);
+    w.f64(p.size);
+    w.u8(p.mode as u8);
+    for v in [p.life, p.origin_x, p.origin_y, p.angle, p.radius] {
+        w.f64(v);
+    }
+    match p.charge {
+        Charge::None => w.u8(0),
+        Charge::Shell { sparks } => {
+            w.u8(1);
+            w.u32(sparks);
+        }
+        Charge::Crackle { fuse } => {
+            w.u8(2);
+            w.f64(fuse);
+        }
+    }
+}
+
+fn write_vine(w: &mut Writer, v: &Vine) {
+    w.points(v.points());
+    for f in [v.x, v.y, v.angle, v.speed, v.turn_speed, v.max_length] {
+        w.f64(f);
+    }
+    w.bool(v.is_grown);
+    w.u64(v.grown_len as u64);
+    w.color(v.color);
+    w.f64(v.line_width);
+}
+
+fn write_lightning(w: &mut Writer, l: &Lightning) {
+    w.points(&l.segments);
+    w.len(l.branches.len());
+    for branch in &l.branches {
+        w.points(branch);
+    }
+    w.color(l.color);
+    w.f64(l.line_width);
+    w.f64(l.life);
+}
+
+// ==================== DECODING ====================
+
+pub(crate) struct Reader<'a>(pub(crate) &'a [u8]);
+
+impl<'a> Reader<'a> {
+    pub(crate) fn take(&mut self, n: usize) -> Result<&'a [u8], SnapshotError> {
+        if self.0.len() < n {
+            return Err(SnapshotError::Truncated);
+        }
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    pub(crate) fn array<const N: usize>(&mut self) -> Result<[u8; N], SnapshotError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, SnapshotError> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn bool(&mut self) -> Result<bool, SnapshotError> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(SnapshotError::Invalid("flag")),
+        }
+    }
+
+    pub(crate) fn u16(&mut self) -> Result<u16, SnapshotError> {
+        Ok(u16::from_le_bytes(self.array()?))
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, SnapshotError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    pub(crate) fn u64(&mut self) -> Result<u64, SnapshotError> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+
+    pub(crate) fn usize(&mut self) -> Result<usize, SnapshotError> {
+        // Saturate so a 64-bit snapshot still loads on 32-bit wasm
+        Ok(usize::try_from(self.u64()?).unwrap_or(usize::MAX))
+    }
+
+    pub(crate) fn f64(&mut self) -> Result<f64, SnapshotError> {
+        Ok(f64::from_le_bytes(self.array()?))
+    }
+
+    pub(crate) fn color(&mut self) -> Result<Rgba, SnapshotError> {
+        let [r, g, b, a] = self.array()?;
+        Ok(Rgba::new(r, g, b, a))
+    }
+
+    /// Read a u32 length followed by that many items
+    pub(crate) fn list<T>(&mut self, read: impl Fn(&mut Self) -> Result<T, SnapshotError>) -> Result<Vec<T>, SnapshotError> {
+        let len = self.u32()? as usize;
+        // Every item takes at least one byte, so this bounds the allocation
+        if len > self.0.len() {
+            return Err(SnapshotError::Truncated);
+        }
+        (0..len).map(|_| read(self)).collect()
+    }
+
+    pub(crate) fn points(&mut self) -> Result<Vec<Point>, SnapshotError> {
+        self.list(|r| Ok(Point { x: r.f64()?, y: r.f64()? }))
+    }
+}
+
+pub(crate) fn read_config(r: &mut Reader) -> Result<EngineConfig, SnapshotError> {
+    Ok(EngineConfig {
+        friction: r.f64()?,
+        gravity: r.f64()?,
+        bounce_restitution: r.f64()?,
+        particle_decay: r.f64()?,
+        constellation_decay: r.f64()?,
+        vortex_decay: r.f64()?,
+        lightning_decay: r.f64()?,
+        vortex_angular_velocity: r.f64()?,
+        vortex_spiral_speed: r.f64()?,
+        lightning_segments: r.u32()?,
+        lightning_jitter: r.f64()?,
+        lightning_branch_jitter: r.f64()?,
+        vine_max_length: r.f64()?,
+        vine_min_length: r.f64()?,
+        smoke_decay: r.f64()?,
+        smoke_growth: r.f64()?,
+        smoke_curl_strength: r.f64()?,
+        boid_decay: r.f64()?,
+        boid_view_radius: r.f64()?,
+        boid_separation: r.f64()?,
+        boid_alignment: r.f64()?,
+        boid_cohesion: r.f64()?,
+        boid_max_speed: r.f64()?,
+        boid_pointer_pull: r.f64()?,
+        constellation_link_distance: r.f64()?,
+    })
+}
+
+pub(crate) fn read_palette(r: &mut Reader) -> Result<Palette, SnapshotError> {
+    let colors = r.list(Reader::color)?;
+    let palette = if r.bool()? {
+        let weights = (0..colors.len()).map(|_| r.f64()).collect::<Result<_, _>>()?;
+        Palette::with_weights(colors, weights)
+    } else {
+        Palette::new(colors)
+    };
+    palette.map_err(|_| SnapshotError::Invalid("palette"))
+}
+
+fn read_particle(r: &mut Reader) -> Result<Particle, SnapshotError> {
+    Ok(Particle {
+        x: r.f64()?,
+        y: r.f64()?,
+        vx: r.f64()?,
+        vy: r.f64()?,
+        prev_x: r.f64()?,
+        prev_y: r.f64()?,
+        color: r.color()?,
+        size: r.f64()?,
+        mode: ParticleMode::try_from(r.u8()?).map_err(|_| SnapshotError::Invalid("particle mode"))?,
+        life: r.f64()?,
+        origin_x: r.f64()?,
+        origin_y: r.f64()?,
+        angle: r.f64()?,
+        radius: r.f64()?,
+        charge: match r.u8()? {
+            0 => Charge::None,
+            1 => Charge::Shell { sparks: r.u32()? },
+            2 => Charge::Crackle { fuse: r.f64()? },
+            _ => return Err(SnapshotError::Invalid("firework charge")),
+        },
+    })
+}
+
+fn read_vine(r: &mut Reader) -> Result<Vine, SnapshotError> {
+    Ok(Vine {
+        points: r.points()?,
+        faded: 0,
+        x: r.f64()?,
+        y: r.f64()?,
+        angle: r.f64()?,
+        speed: r.f64()?,
+        turn_speed: r.f64()?,
+        max_length: r.f64()?,
+        is_grown: r.bool()?,
+        grown_len: r.usize()?,
+        color: r.color()?,
+        line_width: r.f64()?,
+    })
+}
+
+fn read_lightning(r: &mut Reader) -> Result<Lightning, SnapshotError> {
+    Ok(Lightning {
+        segments: r.points()?,
+        branches: r.list(Reader::points)?,
+        color: r.color()?,
+        line_width: r.f64()?,
+        life: r.f64()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn busy_scene() -> DigitalBloom {
+        let mut bloom = DigitalBloom::with_seed(11);
+        bloom.set_palette(Palette::with_weights(vec![Rgba::rgb(1, 2, 3), Rgba::new(4, 5, 6, 7)], vec![1.0, 2.0]).unwrap());
+        bloom.create_vine(200.0, 200.0, 4.0);
+        bloom.create_particles_vortex(100.0, 100.0, 8, 4.0);
+        bloom.create_particles_gravity(150.0, 150.0, 5, 4.0);
+        bloom.create_particles_fireworks(300.0, 380.0, 12, 4.0);
+        bloom.create_particles_smoke(250.0, 300.0, 6, 4.0);
+        bloom.create_particles_boids(200.0, 120.0, 10, 4.0);
+        bloom.set_pointer(320.0, 80.0);
+        bloom.create_lightning(200.0, 20.0, 400.0, 400.0);
+        for _ in 0..20 {
+            bloom.update(400.0, 400.0);
+        }
+        bloom
+    }
+
+    #[test]
+    fn test_restored_engine_continues_identically() {
+        let mut original = busy_scene();
+        let mut restored = DigitalBloom::from_snapshot(&original.snapshot()).unwrap();
+        for bloom in [&mut original, &mut restored] {
+            bloom.create_particles_burst(50.0, 50.0, 6, 4.0);
+            for _ in 0..30 {
+                bloom.update(400.0, 400.0);
+            }
+        }
+        assert_eq!(original.snapshot(), restored.snapshot());
+    }
+
+    #[test]
+    fn test_rejects_bad_input() {
+        let bytes = busy_scene().snapshot();
+        assert!(matches!(DigitalBloom::from_snapshot(b"nope"), Err(SnapshotError::BadMagic)));
+        assert!(matches!(DigitalBloom::from_snapshot(&bytes[..bytes.len() - 1]), Err(SnapshotError::Truncated)));
+
+        let mut future = bytes.clone();
+        future[4] = 99;
+        assert!(matches!(DigitalBloom::from_snapshot(&future), Err(SnapshotError::UnsupportedVersion(99))));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json_round_trip() {
+        let original = busy_scene();
+        let restored = DigitalBloom::from_snapshot_json(&original.snapshot_json()).unwrap();
+        assert_eq!(original.snapshot(), restored.snapshot());
+    }
+}
@@ -0,0 +1,188 @@
+//! SVG export of the current scene
+//!
+//! Vines, lightning and constellation links become stroked paths and
+//! particles become circles, drawn in the same order and with the same widths
+//! and opacities as the web canvas renderer, so exports stay sharp at any
+//! print size.
+
+use std::fmt::Write;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::bloom::DigitalBloom;
+use crate::color::Rgba;
+use crate::link::LINK_WIDTH;
+use crate::Point;
+
+/// Look of an exported SVG
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
+pub struct SvgOptions {
+    /// Full-size backdrop rectangle; `None` leaves the background transparent
+    pub background: Option<Rgba>,
+    /// Add a blur filter to lightning (and a softer one to particles)
+    pub glow: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions {
+            background: Some(Rgba::BACKDROP),
+            glow: true,
+        }
+    }
+}
+
+impl DigitalBloom {
+    /// Export the current scene as a `width` x `height` SVG document
+    pub fn to_svg(&self, width: f64, height: f64) -> String {
+        self.to_svg_with(width, height, &SvgOptions::default())
+    }
+
+    /// Export the current scene as SVG with custom background and glow
+    pub fn to_svg_with(&self, width: f64, height: f64, options: &SvgOptions) -> String {
+        let mut svg = String::new();
+        // Writing to a String can't fail, so the fmt::Results below are ignored
+        let _ = write!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+        );
+
+        if options.glow {
+            svg.push_str(
+                r#"<defs><filter id="glow" x="-50%" y="-50%" width="200%" height="200%"><feGaussianBlur stdDeviation="4" result="blur"/><feMerge><feMergeNode in="blur"/><feMergeNode in="SourceGraphic"/></feMerge></filter><filter id="soft-glow" x="-50%" y="-50%" width="200%" height="200%"><feGaussianBlur stdDeviation="1.5" result="blur"/><feMerge><feMergeNode in="blur"/><feMergeNode in="SourceGraphic"/></feMerge></filter></defs>"#,
+            );
+        }
+        if let Some(background) = options.background {
+            let _ = write!(svg, r#"<rect width="100%" height="100%"{}/>"#, paint("fill", background, 1.0));
+        }
+
+        // Scene coordinates are in engine canvas units; scale them to the document
+        if self.width > 0.0 && self.height > 0.0 {
+            let _ = write!(svg, r#"<g transform="scale({} {})">"#, num(width / self.width), num(height / self.height));
+        } else {
+            svg.push_str("<g>");
+        }
+
+        for lightning in &self.lightnings {
+            let width = lightning.line_width * lightning.life;
+            let filter = if options.glow { r#" filter="url(#glow)""# } else { "" };
+            polyline(&mut svg, &lightning.segments, width, lightning.color, lightning.life, filter);
+            for branch in &lightning.branches {
+                polyline(&mut svg, branch, width * 0.6, lightning.color, lightning.life, "");
+            }
+        }
+
+        // Links sit behind the stars they join
+        for link in &self.links {
+            polyline(&mut svg, &[link.from, link.to], LINK_WIDTH, link.color, link.alpha, "");
+        }
+
+        if options.glow && !self.particles.is_empty() {
+            svg.push_str(r#"<g filter="url(#soft-glow)">"#);
+        }
+        for particle in self.particles.iter() {
+            let radius = particle.size * particle.life;
+            if radius <= 0.0 {
+                continue;
+            }
+            let _ = write!(
+                svg,
+                r#"<circle cx="{}" cy="{}" r="{}"{}/>"#,
+                num(particle.x),
+                num(particle.y),
+                num(radius),
+                paint("fill", particle.color, particle.life)
+            );
+        }
+        if options.glow && !self.particles.is_empty() {
+            svg.push_str("</g>");
+        }
+
+        for vine in self.vines.iter().chain(&self.grown_vines) {
+            polyline(&mut svg, vine.points(), vine.line_width, vine.color, 1.0, "");
+        }
+
+        svg.push_str("</g></svg>");
+        svg
+    }
+}
+
+/// Round to two decimals and drop trailing zeros to keep documents small
+fn num(value: f64) -> String {
+    let rounded = format!("{value:.2}");
+    let trimmed = rounded.trim_end_matches('0').trim_end_matches('.');
+    if trimmed == "-0" { "0".to_string() } else { trimmed.to_string() }
+}
+
+/// ` fill="#rrggbb" fill-opacity=".."` (or stroke), folding the color's alpha into the opacity
+fn paint(attribute: &str, color: Rgba, opacity: f64) -> String {
+    let opacity = (color.a as f64 / 255.0 * opacity).clamp(0.0, 1.0);
+    let mut out = format!(r#" {attribute}="{}""#, Rgba { a: 255, ..color });
+    if opacity < 1.0 {
+        let _ = write!(out, r#" {attribute}-opacity="{}""#, num(opacity));
+    }
+    out
+}
+
+fn polyline(svg: &mut String, points: &[Point], width: f64, color: Rgba, opacity: f64, extra: &str) {
+    if points.len() < 2 || width <= 0.0 {
+        return;
+    }
+    svg.push_str(r#"<path d=""#);
+    for (i, p) in points.iter().enumerate() {
+        let _ = write!(svg, "{}{} {}", if i == 0 { "M" } else { " L" }, num(p.x), num(p.y));
+    }
+    let _ = write!(
+        svg,
+        r#"" fill="none"{} stroke-width="{}" stroke-linecap="round" stroke-linejoin="round"{extra}/>"#,
+        paint("stroke", color, opacity),
+        num(width)
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::palette::Palette;
+
+    #[test]
+    fn test_svg_contains_every_element() {
+        let mut bloom = DigitalBloom::with_seed(6);
+        bloom.set_palette(Palette::new(vec![Rgba::rgb(255, 105, 180)]).unwrap());
+        bloom.set_bounds(200.0, 100.0);
+        bloom.create_particles_burst(50.0, 50.0, 3, 8.0);
+        bloom.create_vine(100.0, 50.0, 4.0);
+        bloom.create_lightning(150.0, 10.0, 200.0, 100.0);
+        bloom.update(200.0, 100.0);
+
+        let svg = bloom.to_svg(400.0, 200.0);
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>"));
+        assert!(svg.contains(r#"<g transform="scale(2 2)">"#));
+        assert_eq!(svg.matches("<circle").count(), 3);
+        assert!(svg.contains(r##"fill="#ff69b4""##));
+        assert!(svg.contains(r#"filter="url(#glow)""#));
+
+        let plain = bloom.to_svg_with(400.0, 200.0, &SvgOptions { background: None, glow: false });
+        assert!(!plain.contains("<filter") && !plain.contains("<rect"));
+    }
+
+    #[test]
+    fn test_svg_draws_constellation_links() {
+        let mut bloom = DigitalBloom::with_seed(6);
+        bloom.create_particles_constellation(200.0, 200.0, 6, 4.0);
+        bloom.update(400.0, 400.0);
+        let paths = bloom.to_svg(400.0, 400.0).matches("<path").count();
+        assert!(paths > 0);
+        assert_eq!(paths, bloom.links().len());
+    }
+
+    #[test]
+    fn test_number_formatting() {
+        assert_eq!(num(2.0), "2");
+        assert_eq!(num(1.256), "1.26");
+        assert_eq!(num(0.5), "0.5");
+        assert_eq!(num(-0.001), "0");
+    }
+}
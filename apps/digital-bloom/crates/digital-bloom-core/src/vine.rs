@@ -0,0 +1,162 @@
+use std::f64::consts::PI;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::color::Rgba;
+use crate::rng::Rng;
+use crate::Point;
+
+/// Most points reserved up front for one vine, however long its config allows
+const MAX_RESERVED_POINTS: usize = 4096;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Vine {
+    // Every point grown so far. Fading only advances `faded` instead of
+    // shifting the buffer, and a recycled vine keeps its capacity.
+    pub(crate) points: Vec<Point>,
+    /// Points dropped off the tail by `fade`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) faded: usize,
+    pub x: f64,
+    pub y: f64,
+    pub angle: f64,
+    pub speed: f64,
+    pub turn_speed: f64,
+    pub max_length: f64,
+    pub is_grown: bool,
+    /// Point count when growth stopped, the baseline for `fade_progress`
+    pub grown_len: usize,
+    pub color: Rgba,
+    pub line_width: f64,
+}
+
+impl Vine {
+    pub fn new(rng: &mut Rng, x: f64, y: f64, color: Rgba, size: f64, max_length: f64, min_length: f64) -> Self {
+        let mut vine = Vine {
+            points: Vec::new(),
+            faded: 0,
+            x,
+            y,
+            angle: 0.0,
+            speed: 0.0,
+            turn_speed: 0.0,
+            max_length: 0.0,
+            is_grown: false,
+            grown_len: 0,
+            color,
+            line_width: 0.0,
+        };
+        vine.reset(rng, Point { x, y }, color, size, max_length, min_length);
+        vine
+    }
+
+    /// Restart as a new vine at `origin`, reusing the point buffer
+    ///
+    /// Draws from `rng` exactly like `new`, so a recycled vine grows the same
+    /// way a fresh one would.
+    pub(crate) fn reset(&mut self, rng: &mut Rng, origin: Point, color: Rgba, size: f64, max_length: f64, min_length: f64) {
+        self.x = origin.x;
+        self.y = origin.y;
+        self.angle = rng.next_f64() * PI * 2.0;
+        self.speed = rng.next_f64() * 2.0 + 0.5;
+        self.turn_speed = rng.next_f64() * 0.12 - 0.06;
+        self.max_length = rng.next_f64() * max_length + min_length;
+        self.is_grown = false;
+        self.grown_len = 0;
+        self.color = color;
+        self.line_width = (rng.next_f64() * size * 0.5) + (size * 0.5);
+
+        self.points.clear();
+        self.faded = 0;
+        // Growth stops one point past `max_length`, so (up to a sane limit)
+        // growing never reallocates
+        self.points.reserve((self.max_length as usize).min(MAX_RESERVED_POINTS) + 2);
+        self.points.push(origin);
+    }
+
+    /// Points still on screen, oldest first
+    pub fn points(&self) -> &[Point] {
+        &self.points[self.faded..]
+    }
+
+    /// Grow by one point. Returns false once the vine is fully grown.
+    pub fn update(&mut self, width: f64, height: f64) -> bool {
+        if self.is_grown {
+            return false;
+        }
+
+        self.angle += self.turn_speed;
+        self.x += self.angle.cos() * self.speed;
+        self.y += self.angle.sin() * self.speed;
+
+        if self.x < 0.0 || self.x > width || self.y < 0.0 || self.y > height {
+            self.finish_growing();
+            return false;
+        }
+
+        self.points.push(Point { x: self.x, y: self.y });
+
+        if self.points.len() as f64 > self.max_length {
+            self.finish_growing();
+            return false;
+        }
+
+        true
+    }
+
+    fn finish_growing(&mut self) {
+        self.is_grown = true;
+        self.grown_len = self.points.len();
+    }
+
+    /// How far a grown vine has faded: 0.0 while growing, approaching 1.0 as it disappears
+    pub fn fade_progress(&self) -> f64 {
+        if !self.is_grown || self.grown_len == 0 {
+            return 0.0;
+        }
+        1.0 - self.points().len() as f64 / self.grown_len as f64
+    }
+
+    /// Shrink a grown vine from its tail. Returns false once it has faded out.
+    pub fn fade(&mut self) -> bool {
+        if self.points().is_empty() {
+            return false;
+        }
+        self.faded += 1;
+        self.points().len() > 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fade_progress() {
+        let mut vine = Vine::new(&mut Rng::new(1), 200.0, 200.0, Rgba::WHITE, 4.0, 20.0, 10.0);
+        while vine.update(400.0, 400.0) {}
+        assert_eq!(vine.fade_progress(), 0.0);
+        let grown_len = vine.grown_len;
+        assert!(grown_len > 1);
+
+        vine.fade();
+        assert!((vine.fade_progress() - 1.0 / grown_len as f64).abs() < 1e-9);
+        while vine.fade() {}
+        assert!(vine.fade_progress() > 0.9);
+    }
+
+    #[test]
+    fn test_reset_matches_new() {
+        let mut recycled = Vine::new(&mut Rng::new(1), 200.0, 200.0, Rgba::WHITE, 4.0, 20.0, 10.0);
+        while recycled.update(400.0, 400.0) {}
+        while recycled.fade() {}
+
+        let (mut a, mut b) = (Rng::new(2), Rng::new(2));
+        recycled.reset(&mut a, Point { x: 50.0, y: 60.0 }, Rgba::rgb(1, 2, 3), 6.0, 30.0, 5.0);
+        let fresh = Vine::new(&mut b, 50.0, 60.0, Rgba::rgb(1, 2, 3), 6.0, 30.0, 5.0);
+        assert_eq!(recycled, fresh);
+        assert!(recycled.points.capacity() > recycled.max_length as usize);
+    }
+}
@@ -0,0 +1,123 @@
+//! `update` (and `render_into`) must not touch the heap once a scene has warmed up
+//!
+//! Installs a counting global allocator, so it lives in its own test binary
+//! rather than next to the unit tests.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use digital_bloom_core::{BudgetPolicy, DigitalBloom, ParticleMode};
+#[cfg(feature = "render")]
+use digital_bloom_core::{Framebuffer, RenderOptions};
+
+const WIDTH: f64 = 400.0;
+const HEIGHT: f64 = 400.0;
+/// Long enough for the longest vine to grow and fade out completely
+const FRAMES: usize = 900;
+
+thread_local! {
+    // Per thread, so allocations by the test harness don't count
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc_zeroed(layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+/// Vines, particles in every mode and a few bolts
+fn fill(bloom: &mut DigitalBloom) {
+    for i in 0..20 {
+        bloom.create_vine(120.0 + (i % 5) as f64 * 40.0, 120.0 + (i / 5) as f64 * 40.0, 4.0);
+    }
+    for mode in [
+        ParticleMode::Gravity,
+        ParticleMode::Bounce,
+        ParticleMode::Burst,
+        ParticleMode::Constellation,
+        ParticleMode::Vortex,
+        ParticleMode::Fireworks,
+        ParticleMode::Smoke,
+        ParticleMode::Boids,
+    ] {
+        bloom.create(mode, 200.0, 200.0, 40, 4.0);
+    }
+    for i in 0..5 {
+        bloom.create_lightning(50.0 + i as f64 * 75.0, 10.0, WIDTH, HEIGHT);
+    }
+}
+
+#[test]
+fn test_update_does_not_allocate_once_warm() {
+    let mut bloom = DigitalBloom::with_seed(0x5EED);
+    bloom.set_bounds(WIDTH, HEIGHT);
+    bloom.set_budget_policy(BudgetPolicy::DynamicDecay);
+    bloom.set_pointer(300.0, 100.0);
+
+    // One full lifetime grows every list and the vine pool to the scene's size
+    fill(&mut bloom);
+    for _ in 0..FRAMES {
+        bloom.update(WIDTH, HEIGHT);
+    }
+    assert_eq!(bloom.scene_load(), 0);
+
+    fill(&mut bloom);
+    let before = allocations();
+    for _ in 0..FRAMES {
+        bloom.update(WIDTH, HEIGHT);
+        bloom.update_dt(1.0 / 60.0);
+    }
+    assert_eq!(allocations() - before, 0);
+    assert_eq!(bloom.scene_load(), 0);
+}
+
+#[cfg(feature = "render")]
+#[test]
+fn test_render_into_does_not_allocate_once_warm() {
+    let mut bloom = DigitalBloom::with_seed(0x5EED);
+    bloom.set_bounds(WIDTH, HEIGHT);
+    bloom.set_budget_policy(BudgetPolicy::DynamicDecay);
+    bloom.set_pointer(300.0, 100.0);
+    // Small, so the debug-build rasterizer keeps the test quick
+    let mut frame = Framebuffer::new(WIDTH as usize / 5, HEIGHT as usize / 5);
+    let options = RenderOptions::default();
+
+    fill(&mut bloom);
+    for _ in 0..FRAMES {
+        bloom.update(WIDTH, HEIGHT);
+        bloom.render_into(&mut frame, &options);
+    }
+
+    fill(&mut bloom);
+    let before = allocations();
+    for _ in 0..FRAMES {
+        bloom.update(WIDTH, HEIGHT);
+        bloom.render_into(&mut frame, &options);
+    }
+    assert_eq!(allocations() - before, 0);
+}
@@ -0,0 +1,160 @@
+//! The C API as the watch app drives it, with the watch's dynamic decay budget
+//!
+//! The scene matches `digital-bloom-core/benches/common/mod.rs` (800 particles,
+//! 50 vines, 20 bolts, fixed seed), built through the exported functions.
+//! Compare commits with `cargo bench -- --save-baseline before` and
+//! `cargo bench -- --baseline before`.
+
+use std::ffi::c_char;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use digital_bloom_core::ParticleMode;
+use digital_bloom_ffi::*;
+
+const WIDTH: f64 = 400.0;
+const HEIGHT: f64 = 400.0;
+/// Room for every vine, grown vine and bolt point in the scene
+const POINTS_CAPACITY: usize = 32 * 1024;
+
+/// Owns an engine pointer so criterion can drop it outside the timed section
+struct Engine(*mut OpaqueDigitalBloom);
+
+impl Drop for Engine {
+    fn drop(&mut self) {
+        unsafe { digital_bloom_destroy(self.0) };
+    }
+}
+
+impl Engine {
+    fn from_snapshot(bytes: &[u8]) -> Self {
+        Engine(unsafe { digital_bloom_create_from_snapshot(bytes.as_ptr(), bytes.len()) })
+    }
+
+    fn snapshot(&self) -> Vec<u8> {
+        unsafe {
+            let len = digital_bloom_snapshot(self.0, std::ptr::null_mut(), 0);
+            let mut bytes = vec![0u8; len];
+            digital_bloom_snapshot(self.0, bytes.as_mut_ptr(), len);
+            bytes
+        }
+    }
+}
+
+fn grid(i: usize) -> (f64, f64) {
+    (20.0 + (i % 10) as f64 * 36.0, 20.0 + (i / 10 % 10) as f64 * 36.0)
+}
+
+fn steady_state() -> Engine {
+    let engine = Engine(digital_bloom_create_with_seed(0x5EED));
+    let ptr = engine.0;
+    unsafe {
+        digital_bloom_set_bounds(ptr, WIDTH, HEIGHT);
+        digital_bloom_set_budget_policy(ptr, DigitalBloomBudget::None as u8, 0);
+        for i in 0..50 {
+            let (x, y) = grid(i);
+            digital_bloom_create_particles(ptr, ParticleMode::Vine as u8, x, y, 1, 4.0);
+        }
+        for _ in 0..30 {
+            digital_bloom_update(ptr, WIDTH, HEIGHT);
+        }
+        let modes = [ParticleMode::Gravity, ParticleMode::Bounce, ParticleMode::Burst, ParticleMode::Constellation, ParticleMode::Vortex];
+        for i in 0..40 {
+            let (x, y) = grid(i);
+            digital_bloom_create_particles(ptr, modes[i % modes.len()] as u8, x, y, 20, 4.0);
+        }
+        for i in 0..20 {
+            let (x, _) = grid(i);
+            digital_bloom_create_particles(ptr, ParticleMode::Lightning as u8, x, 10.0, 1, 4.0);
+        }
+        digital_bloom_set_budget_policy(ptr, DigitalBloomBudget::DynamicDecay as u8, 0);
+        assert_eq!(digital_bloom_get_lightning_count(ptr), 20);
+    }
+    engine
+}
+
+/// Caller-owned buffers, allocated once like the Swift renderer does
+struct Buffers {
+    particles: Vec<CParticle>,
+    vines: Vec<CVine>,
+    lightning: Vec<CLightning>,
+    points: Vec<CPoint>,
+}
+
+impl Buffers {
+    fn new() -> Self {
+        unsafe {
+            Buffers {
+                particles: vec![std::mem::zeroed(); 2048],
+                vines: vec![std::mem::zeroed(); 64],
+                lightning: vec![std::mem::zeroed(); 32],
+                points: vec![CPoint { x: 0.0, y: 0.0 }; POINTS_CAPACITY],
+            }
+        }
+    }
+
+    /// Copy everything a frame draws out of the engine
+    unsafe fn read_frame(&mut self, ptr: *const OpaqueDigitalBloom) -> usize {
+        unsafe {
+            digital_bloom_get_particles(ptr, self.particles.as_mut_ptr(), self.particles.len())
+                + digital_bloom_get_vines(ptr, self.vines.as_mut_ptr(), self.vines.len(), self.points.as_mut_ptr(), POINTS_CAPACITY)
+                + digital_bloom_get_grown_vines(ptr, self.vines.as_mut_ptr(), self.vines.len(), self.points.as_mut_ptr(), POINTS_CAPACITY)
+                + digital_bloom_get_lightning(ptr, self.lightning.as_mut_ptr(), self.lightning.len(), self.points.as_mut_ptr(), POINTS_CAPACITY)
+        }
+    }
+}
+
+fn frame(c: &mut Criterion) {
+    let snapshot = steady_state().snapshot();
+    let mut buffers = Buffers::new();
+
+    let mut group = c.benchmark_group("ffi");
+    group.bench_function("update", |b| {
+        b.iter_batched(
+            || Engine::from_snapshot(&snapshot),
+            |engine| {
+                unsafe { digital_bloom_update(engine.0, WIDTH, HEIGHT) };
+                engine
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    // One watch frame: step, then copy out everything to draw
+    group.bench_function("frame", |b| {
+        b.iter_batched(
+            || Engine::from_snapshot(&snapshot),
+            |engine| {
+                unsafe {
+                    digital_bloom_update_dt(engine.0, 1.0 / 60.0);
+                    buffers.read_frame(engine.0);
+                }
+                engine
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    let engine = Engine::from_snapshot(&snapshot);
+    group.bench_function("get_particles", |b| {
+        b.iter(|| unsafe { digital_bloom_get_particles(engine.0, buffers.particles.as_mut_ptr(), buffers.particles.len()) })
+    });
+    group.bench_function("read_frame", |b| b.iter(|| unsafe { buffers.read_frame(engine.0) }));
+    group.finish();
+}
+
+fn export(c: &mut Criterion) {
+    let engine = steady_state();
+    let mut snapshot = vec![0u8; engine.snapshot().len()];
+    let mut svg = vec![0 as c_char; 1 << 20];
+
+    let mut group = c.benchmark_group("ffi_export");
+    group.bench_function("snapshot", |b| {
+        b.iter(|| unsafe { digital_bloom_snapshot(engine.0, snapshot.as_mut_ptr(), snapshot.len()) })
+    });
+    group.bench_function("to_svg", |b| {
+        b.iter(|| unsafe { digital_bloom_to_svg(engine.0, WIDTH, HEIGHT, true, svg.as_mut_ptr(), svg.len()) })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, frame, export);
+criterion_main!(benches);
@@ -12,12 +12,17 @@ fn main() {
     match cbindgen::Builder::new()
         .with_crate(crate_dir)
         .with_language(cbindgen::Language::C)
-        .with_parse_deps(false)
+        // Parse the core crate so shared types like ParticleMode land in the header
+        .with_parse_deps(true)
+        .with_parse_include(&["digital-bloom-core"])
+        .include_item("ParticleMode")
+        .include_item("DigitalBloomBudget")
         .generate()
     {
         Ok(bindings) => {
             bindings.write_to_file(&output_file);
             println!("cargo:rerun-if-changed=src/lib.rs");
+            println!("cargo:rerun-if-changed=../digital-bloom-core/src");
         }
         Err(err) => {
             eprintln!("Warning: cbindgen failed: {:?}", err);
@@ -11,8 +11,73 @@ typedef enum DigitalBloomError {
   NullPointer = 1,
   InvalidMode = 2,
   OutOfMemory = 3,
+  InvalidPalette = 4,
+  InvalidSnapshot = 5,
+  InvalidPolicy = 6,
 } DigitalBloomError;
 
+/**
+ * Brush mode shared by the engine, the C header and the TypeScript bindings
+ *
+ * The discriminants are part of the FFI contract and must not change.
+ */
+enum ParticleMode
+#if __STDC_VERSION__ >= 202311L
+  : uint8_t
+#endif // __STDC_VERSION__ >= 202311L
+ {
+  Vine = 0,
+  Gravity = 1,
+  Bounce = 2,
+  Burst = 3,
+  Lightning = 4,
+  Constellation = 5,
+  Vortex = 6,
+  Fireworks = 7,
+  Smoke = 8,
+  Boids = 9,
+};
+#if __STDC_VERSION__ >= 202311L
+typedef enum ParticleMode ParticleMode;
+#else
+typedef uint8_t ParticleMode;
+#endif // __STDC_VERSION__ >= 202311L
+
+/**
+ * Particle budget policies accepted by digital_bloom_set_budget_policy()
+ */
+enum DigitalBloomBudget
+#if __STDC_VERSION__ >= 202311L
+  : uint8_t
+#endif // __STDC_VERSION__ >= 202311L
+ {
+  /**
+   * No limit at all
+   */
+  None = 0,
+  /**
+   * Keep at most max_particles, dropping the oldest first
+   */
+  HardCap = 1,
+  /**
+   * No hard limit; decay speeds up as the scene fills (watch default)
+   */
+  DynamicDecay = 2,
+  /**
+   * Keep at most max_particles, dropping random ones
+   */
+  RandomCull = 3,
+  /**
+   * Keep at most max_particles, dropping the smallest first
+   */
+  SmallestFirst = 4,
+};
+#if __STDC_VERSION__ >= 202311L
+typedef enum DigitalBloomBudget DigitalBloomBudget;
+#else
+typedef uint8_t DigitalBloomBudget;
+#endif // __STDC_VERSION__ >= 202311L
+
 /**
  * Opaque pointer to DigitalBloom engine (hides Rust internals from C)
  */
@@ -20,17 +85,67 @@ typedef struct OpaqueDigitalBloom {
   uint8_t _private[0];
 } OpaqueDigitalBloom;
 
+/**
+ * C-compatible physics configuration (mirrors EngineConfig)
+ *
+ * All rates are per fixed 1/60s step. Start from digital_bloom_default_config()
+ * and override the fields you want to tune.
+ */
+typedef struct DigitalBloomConfig {
+  double friction;
+  double gravity;
+  double bounce_restitution;
+  double particle_decay;
+  double constellation_decay;
+  double vortex_decay;
+  double lightning_decay;
+  double vortex_angular_velocity;
+  double vortex_spiral_speed;
+  uint32_t lightning_segments;
+  double lightning_jitter;
+  double lightning_branch_jitter;
+  double vine_max_length;
+  double vine_min_length;
+  double smoke_decay;
+  double smoke_growth;
+  double smoke_curl_strength;
+  double boid_decay;
+  double boid_view_radius;
+  double boid_separation;
+  double boid_alignment;
+  double boid_cohesion;
+  double boid_max_speed;
+  double boid_pointer_pull;
+  double constellation_link_distance;
+} DigitalBloomConfig;
+
+/**
+ * C-compatible RGBA color
+ */
+typedef struct CColor {
+  uint8_t r;
+  uint8_t g;
+  uint8_t b;
+  uint8_t a;
+} CColor;
+
 /**
  * C-compatible particle representation
  */
 typedef struct CParticle {
   double x;
   double y;
+  /**
+   * Position before the last step; draw at prev + (x - prev) * alpha
+   */
+  double prev_x;
+  double prev_y;
   double size;
   double life;
   uint8_t color_r;
   uint8_t color_g;
   uint8_t color_b;
+  uint8_t color_a;
 } CParticle;
 
 /**
@@ -50,7 +165,12 @@ typedef struct CVine {
   uint8_t color_r;
   uint8_t color_g;
   uint8_t color_b;
+  uint8_t color_a;
   double line_width;
+  /**
+   * 0.0 while growing; rises toward 1.0 as a grown vine fades out
+   */
+  double fade_progress;
 } CVine;
 
 /**
@@ -62,10 +182,49 @@ typedef struct CLightning {
   uint8_t color_r;
   uint8_t color_g;
   uint8_t color_b;
+  uint8_t color_a;
   double line_width;
   double life;
 } CLightning;
 
+/**
+ * One lightning branch polyline in the table filled by digital_bloom_get_lightning_branches()
+ *
+ * The points live in the shared point buffer at [points_offset, points_offset + points_len).
+ */
+typedef struct CLightningBranch {
+  /**
+   * Index of the parent bolt, in digital_bloom_get_lightning() order
+   */
+  uintptr_t lightning_index;
+  uintptr_t points_offset;
+  uintptr_t points_len;
+} CLightningBranch;
+
+/**
+ * C-compatible constellation link: one line segment between two stars
+ */
+typedef struct CLink {
+  /**
+   * Indices of the two stars, in digital_bloom_get_particles() order
+   */
+  uintptr_t a;
+  uintptr_t b;
+  double x1;
+  double y1;
+  double x2;
+  double y2;
+  uint8_t color_r;
+  uint8_t color_g;
+  uint8_t color_b;
+  uint8_t color_a;
+  double line_width;
+  /**
+   * Opacity, fading with distance and with the dimmer star's life
+   */
+  double alpha;
+} CLink;
+
 /**
  * Create a new DigitalBloom engine
  *
@@ -73,6 +232,26 @@ typedef struct CLightning {
  */
 struct OpaqueDigitalBloom *digital_bloom_create(void);
 
+/**
+ * Create a new DigitalBloom engine with a fixed random seed
+ *
+ * The same seed and the same sequence of calls always produce the same
+ * animation, which makes bug reports reproducible.
+ * Returns an opaque pointer that must be freed with digital_bloom_destroy()
+ */
+struct OpaqueDigitalBloom *digital_bloom_create_with_seed(uint64_t seed);
+
+/**
+ * Create a new DigitalBloom engine with custom physics
+ *
+ * A null config uses the defaults.
+ * Returns an opaque pointer that must be freed with digital_bloom_destroy()
+ *
+ * # Safety
+ * - config must be null or point to a valid DigitalBloomConfig
+ */
+struct OpaqueDigitalBloom *digital_bloom_create_with_config(const struct DigitalBloomConfig *config);
+
 /**
  * Destroy a DigitalBloom engine and free its memory
  *
@@ -93,17 +272,279 @@ enum DigitalBloomError digital_bloom_update(struct OpaqueDigitalBloom *ptr,
                                             double width,
                                             double height);
 
+/**
+ * Advance the simulation by `dt_seconds` of wall-clock time
+ *
+ * Runs as many fixed 1/60s steps as fit and carries the remainder over, so
+ * the animation speed doesn't depend on the display refresh rate.
+ * Use digital_bloom_set_bounds() to tell the engine the canvas size.
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ */
+enum DigitalBloomError digital_bloom_update_dt(struct OpaqueDigitalBloom *ptr, double dt_seconds);
+
+/**
+ * Set the canvas size used by digital_bloom_update_dt() and lightning creation
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ */
+enum DigitalBloomError digital_bloom_set_bounds(struct OpaqueDigitalBloom *ptr,
+                                                double width,
+                                                double height);
+
+/**
+ * Draw boids toward (x, y) until digital_bloom_clear_pointer() is called
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ */
+enum DigitalBloomError digital_bloom_set_pointer(struct OpaqueDigitalBloom *ptr,
+                                                 double x,
+                                                 double y);
+
+/**
+ * Stop drawing boids toward the pointer
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ */
+enum DigitalBloomError digital_bloom_clear_pointer(struct OpaqueDigitalBloom *ptr);
+
+/**
+ * Get the interpolation alpha (0..1) left over by digital_bloom_update_dt()
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ */
+double digital_bloom_get_alpha(const struct OpaqueDigitalBloom *ptr);
+
+/**
+ * Get the default physics configuration
+ */
+struct DigitalBloomConfig digital_bloom_default_config(void);
+
+/**
+ * Replace the engine's physics configuration; takes effect on the next step
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ * - config must point to a valid DigitalBloomConfig
+ */
+enum DigitalBloomError digital_bloom_set_config(struct OpaqueDigitalBloom *ptr,
+                                                const struct DigitalBloomConfig *config);
+
+/**
+ * Read the engine's current physics configuration
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ * - out_config must point to writable memory for one DigitalBloomConfig
+ */
+enum DigitalBloomError digital_bloom_get_config(const struct OpaqueDigitalBloom *ptr,
+                                                struct DigitalBloomConfig *out_config);
+
+/**
+ * Replace the palette new elements are colored from
+ *
+ * `weights` may be null for a uniform pick; otherwise it must hold `len`
+ * non-negative relative weights. Returns `InvalidPalette` for an empty
+ * palette or bad weights.
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ * - colors must point to `len` CColor values
+ * - weights must be null or point to `len` doubles
+ */
+enum DigitalBloomError digital_bloom_set_palette(struct OpaqueDigitalBloom *ptr,
+                                                 const struct CColor *colors,
+                                                 const double *weights,
+                                                 uintptr_t len);
+
+/**
+ * Switch to a built-in palette by name ("bloom", "sunset", "ocean", "forest", "mono")
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ * - name must be a valid NUL-terminated string
+ */
+enum DigitalBloomError digital_bloom_set_builtin_palette(struct OpaqueDigitalBloom *ptr,
+                                                         const char *name);
+
+/**
+ * Get the number of colors in the current palette
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ */
+uintptr_t digital_bloom_get_palette_len(const struct OpaqueDigitalBloom *ptr);
+
+/**
+ * Copy the current palette into caller-provided buffers
+ *
+ * Unweighted palettes report a weight of 1.0 for every color.
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ * - out_colors must point to an array of at least capacity elements
+ * - out_weights must be null or point to an array of at least capacity elements
+ * - Returns the actual number of colors written
+ */
+uintptr_t digital_bloom_get_palette(const struct OpaqueDigitalBloom *ptr,
+                                    struct CColor *out_colors,
+                                    double *out_weights,
+                                    uintptr_t capacity);
+
+/**
+ * Choose how the particle count is kept in check
+ *
+ * `policy` must be one of the `DigitalBloomBudget` values; `max_particles` is
+ * ignored by the uncapped policies.
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ */
+enum DigitalBloomError digital_bloom_set_budget_policy(struct OpaqueDigitalBloom *ptr,
+                                                       uint8_t policy,
+                                                       uintptr_t max_particles);
+
+/**
+ * Get the current budget policy as a `DigitalBloomBudget` value
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ * - out_max_particles must be null or point to a valid usize; it receives the cap (0 if uncapped)
+ */
+uint8_t digital_bloom_get_budget_policy(const struct OpaqueDigitalBloom *ptr,
+                                        uintptr_t *out_max_particles);
+
+/**
+ * Total on-screen elements (particles plus every vine and lightning point)
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ */
+uintptr_t digital_bloom_get_scene_load(const struct OpaqueDigitalBloom *ptr);
+
+/**
+ * Restart the engine's random sequence from `seed` without clearing the scene
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ */
+enum DigitalBloomError digital_bloom_set_seed(struct OpaqueDigitalBloom *ptr, uint64_t seed);
+
+/**
+ * Get the seed the engine was created with (or last reseeded with)
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ */
+uint64_t digital_bloom_get_seed(const struct OpaqueDigitalBloom *ptr);
+
+/**
+ * Serialize the full engine state into a caller-provided buffer
+ *
+ * Always returns the snapshot size in bytes; the buffer is only written when
+ * capacity is large enough. Pass a null buffer to query the size first.
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ * - out_buffer must be null or point to at least capacity bytes
+ */
+uintptr_t digital_bloom_snapshot(const struct OpaqueDigitalBloom *ptr,
+                                 uint8_t *out_buffer,
+                                 uintptr_t capacity);
+
+/**
+ * Replace the engine state with a snapshot from digital_bloom_snapshot()
+ *
+ * The engine is left untouched if the snapshot is rejected.
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ * - data must point to at least len bytes
+ */
+enum DigitalBloomError digital_bloom_restore(struct OpaqueDigitalBloom *ptr,
+                                             const uint8_t *data,
+                                             uintptr_t len);
+
+/**
+ * Create a new DigitalBloom engine from a snapshot
+ *
+ * Returns null if the snapshot is invalid, otherwise an opaque pointer that
+ * must be freed with digital_bloom_destroy()
+ *
+ * # Safety
+ * - data must point to at least len bytes
+ */
+struct OpaqueDigitalBloom *digital_bloom_create_from_snapshot(const uint8_t *data, uintptr_t len);
+
+/**
+ * Export the current scene as a NUL-terminated SVG document
+ *
+ * Always returns the buffer size needed, including the NUL; the buffer is only
+ * written when capacity is large enough. Pass a null buffer to query the size.
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ * - out_buffer must be null or point to at least capacity bytes
+ */
+uintptr_t digital_bloom_to_svg(const struct OpaqueDigitalBloom *ptr,
+                               double width,
+                               double height,
+                               bool glow,
+                               char *out_buffer,
+                               uintptr_t capacity);
+
+/**
+ * Start logging every call made on the engine, discarding any previous log
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ */
+enum DigitalBloomError digital_bloom_start_recording(struct OpaqueDigitalBloom *ptr);
+
+/**
+ * Stop logging and discard the log; fetch it first with digital_bloom_get_recording()
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ */
+enum DigitalBloomError digital_bloom_stop_recording(struct OpaqueDigitalBloom *ptr);
+
+/**
+ * Serialize the recording in progress into a caller-provided buffer
+ *
+ * Returns the recording size in bytes (0 when not recording); the buffer is
+ * only written when capacity is large enough. Pass a null buffer to query the
+ * size first.
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ * - out_buffer must be null or point to at least capacity bytes
+ */
+uintptr_t digital_bloom_get_recording(const struct OpaqueDigitalBloom *ptr,
+                                      uint8_t *out_buffer,
+                                      uintptr_t capacity);
+
+/**
+ * Create a new DigitalBloom engine by replaying a recording
+ *
+ * Returns null if the recording is invalid, otherwise an opaque pointer that
+ * must be freed with digital_bloom_destroy()
+ *
+ * # Safety
+ * - data must point to at least len bytes
+ */
+struct OpaqueDigitalBloom *digital_bloom_create_from_recording(const uint8_t *data, uintptr_t len);
+
 /**
  * Create particles with specified mode
  *
- * # Modes
- * - 0: Vine
- * - 1: Gravity
- * - 2: Bounce
- * - 3: Burst
- * - 4: Lightning
- * - 5: Constellation
- * - 6: Vortex
+ * `mode` must be one of the `ParticleMode` values; anything else returns
+ * `InvalidMode`. Lightning uses the current canvas size (see digital_bloom_set_bounds()).
  *
  * # Safety
  * - ptr must be a valid pointer returned from digital_bloom_create()
@@ -131,6 +572,14 @@ uintptr_t digital_bloom_get_particle_count(const struct OpaqueDigitalBloom *ptr)
  */
 uintptr_t digital_bloom_get_vine_count(const struct OpaqueDigitalBloom *ptr);
 
+/**
+ * Get the current number of grown vines that are fading out
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ */
+uintptr_t digital_bloom_get_grown_vine_count(const struct OpaqueDigitalBloom *ptr);
+
 /**
  * Get the current number of active lightning bolts
  *
@@ -169,9 +618,28 @@ uintptr_t digital_bloom_get_vines(const struct OpaqueDigitalBloom *ptr,
                                   struct CPoint *out_points,
                                   uintptr_t points_capacity);
 
+/**
+ * Get grown vines that are fading out, for path rendering
+ *
+ * Draw them like active vines, fading with `fade_progress` if desired.
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ * - out_vines must point to an array of at least buffer_capacity CVine elements
+ * - out_points must point to an array large enough to hold all vine points
+ * - Returns the actual number of vines written
+ */
+uintptr_t digital_bloom_get_grown_vines(const struct OpaqueDigitalBloom *ptr,
+                                        struct CVine *out_vines,
+                                        uintptr_t buffer_capacity,
+                                        struct CPoint *out_points,
+                                        uintptr_t points_capacity);
+
 /**
  * Get lightning bolts for path rendering
  *
+ * Only the main bolt is written; fetch its branches with digital_bloom_get_lightning_branches().
+ *
  * # Safety
  * - ptr must be a valid pointer returned from digital_bloom_create()
  * - out_lightning must point to an array of at least buffer_capacity elements
@@ -184,6 +652,85 @@ uintptr_t digital_bloom_get_lightning(const struct OpaqueDigitalBloom *ptr,
                                       struct CPoint *out_segments,
                                       uintptr_t segments_capacity);
 
+/**
+ * Get the total number of branches across all lightning bolts
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ */
+uintptr_t digital_bloom_get_lightning_branch_count(const struct OpaqueDigitalBloom *ptr);
+
+/**
+ * Get lightning branches as a table of offsets into a shared point buffer
+ *
+ * Branches are drawn with their parent bolt's color, width and life.
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ * - out_branches must point to an array of at least branches_capacity elements
+ * - out_points must point to an array of at least points_capacity elements
+ * - Returns the actual number of branches written
+ */
+uintptr_t digital_bloom_get_lightning_branches(const struct OpaqueDigitalBloom *ptr,
+                                               struct CLightningBranch *out_branches,
+                                               uintptr_t branches_capacity,
+                                               struct CPoint *out_points,
+                                               uintptr_t points_capacity);
+
+/**
+ * Get the number of constellation links from the last update
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ */
+uintptr_t digital_bloom_get_link_count(const struct OpaqueDigitalBloom *ptr);
+
+/**
+ * Get the line segments joining nearby constellation stars
+ *
+ * Draw them before the particles so the stars sit on top.
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ * - out_buffer must point to an array of at least buffer_capacity elements
+ * - Returns the actual number of links written to the buffer
+ */
+uintptr_t digital_bloom_get_links(const struct OpaqueDigitalBloom *ptr,
+                                  struct CLink *out_buffer,
+                                  uintptr_t buffer_capacity);
+
+/**
+ * Find the particles within radius of (x, y)
+ *
+ * Indices are ascending and refer to the order of digital_bloom_get_particles().
+ *
+ * # Safety
+ * - ptr must be a valid pointer returned from digital_bloom_create()
+ * - out_indices must point to an array of at least buffer_capacity elements,
+ *   or be null to only count the matches
+ * - Returns the total number of matches; only the first buffer_capacity are written
+ */
+uintptr_t digital_bloom_query_radius(const struct OpaqueDigitalBloom *ptr,
+                                     double x,
+                                     double y,
+                                     double radius,
+                                     uintptr_t *out_indices,
+                                     uintptr_t buffer_capacity);
+
+/**
+ * Find the particles inside a rectangle (edges included)
+ *
+ * # Safety
+ * - Same contract as digital_bloom_query_radius()
+ */
+uintptr_t digital_bloom_query_rect(const struct OpaqueDigitalBloom *ptr,
+                                   double min_x,
+                                   double min_y,
+                                   double max_x,
+                                   double max_y,
+                                   uintptr_t *out_indices,
+                                   uintptr_t buffer_capacity);
+
 /**
  * Clear all particles and reset the simulation
  *
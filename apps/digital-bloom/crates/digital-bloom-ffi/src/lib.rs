@@ -1,383 +1,13 @@
 //! Digital Bloom FFI Layer for watchOS
-//! 
+//!
 //! This crate provides C-compatible FFI bindings for the Digital Bloom particle physics engine.
-//! It reuses the existing physics code from the WASM crate and exposes it via C-compatible functions.
+//! All simulation logic lives in `digital-bloom-core`; this layer only converts to C types.
 
 #![allow(non_camel_case_types)]
 
-use std::f64::consts::PI;
+use std::ffi::{c_char, CStr};
 
-// Simple random number generator using system time (no dependencies)
-fn random() -> f64 {
-    // Use a simple LCG-based RNG for native targets
-    use std::cell::Cell;
-    thread_local! {
-        static SEED: Cell<u64> = Cell::new(1);
-    }
-
-    SEED.with(|seed| {
-        let mut s = seed.get();
-        s = s.wrapping_mul(6364136223846793005).wrapping_add(1);
-        seed.set(s);
-        (s >> 33) as f64 / (1u64 << 31) as f64
-    })
-}
-
-// Core physics types (simplified from WASM version, no wasm-bindgen)
-#[derive(Clone)]
-pub struct Point {
-    pub x: f64,
-    pub y: f64,
-}
-
-pub struct Particle {
-    x: f64,
-    y: f64,
-    vx: f64,
-    vy: f64,
-    color: String,
-    size: f64,
-    mode: String,
-    life: f64,
-    decay: f64,
-    origin_x: f64,
-    origin_y: f64,
-    angle: f64,
-    radius: f64,
-}
-
-impl Particle {
-    pub fn new(x: f64, y: f64, vx: f64, vy: f64, color: String, size: f64, mode: String) -> Self {
-        Particle {
-            x, y, vx, vy, color, size, mode,
-            life: 1.0,
-            decay: 0.005,
-            origin_x: x,
-            origin_y: y,
-            angle: 0.0,
-            radius: 0.0,
-        }
-    }
-
-    pub fn new_vortex(x: f64, y: f64, origin_x: f64, origin_y: f64, angle: f64, radius: f64, color: String, size: f64) -> Self {
-        let vx = angle.cos() * 2.0;
-        let vy = angle.sin() * 2.0;
-        Particle {
-            x, y, vx, vy, color, size,
-            mode: "vortex".to_string(),
-            life: 1.0,
-            decay: 0.003,
-            origin_x, origin_y, angle, radius,
-        }
-    }
-
-    pub fn update(&mut self, width: f64, height: f64, total_particles: usize) -> bool {
-        if self.mode == "vortex" {
-            self.angle += 0.08;
-            self.radius -= 0.5;
-            if self.radius < 0.0 { self.radius = 0.0; }
-            self.x = self.origin_x + self.angle.cos() * self.radius;
-            self.y = self.origin_y + self.angle.sin() * self.radius;
-        } else {
-            self.vx *= 0.99;
-            self.vy *= 0.99;
-            if self.mode == "gravity" { self.vy += 0.3; }
-            self.x += self.vx;
-            self.y += self.vy;
-            if self.mode == "bounce" {
-                let bounce = 0.7;
-                if self.x < self.size || self.x > width - self.size {
-                    self.vx *= -bounce;
-                    self.x = self.x.max(self.size).min(width - self.size);
-                }
-                if self.y < self.size || self.y > height - self.size {
-                    self.vy *= -bounce;
-                    self.y = self.y.max(self.size).min(height - self.size);
-                }
-            }
-        }
-
-        // Dynamic decay: Particles fade faster when there are many of them
-        let load_factor = if total_particles < 200 {
-            1.0  // Normal decay
-        } else if total_particles < 400 {
-            1.0 + (total_particles - 200) as f64 * 0.0025  // 1.0 → 1.5
-        } else if total_particles < 800 {
-            1.5 + (total_particles - 400) as f64 * 0.0025  // 1.5 → 2.5
-        } else {
-            2.5 + ((total_particles - 800) as f64 * 0.00375).min(1.5)  // 2.5 → 4.0 (cap)
-        };
-
-        self.life -= self.decay * load_factor;
-        self.life > 0.0 && self.x > -50.0 && self.x < width + 50.0 && self.y > -50.0 && self.y < height + 50.0
-    }
-
-    pub fn x(&self) -> f64 { self.x }
-    pub fn y(&self) -> f64 { self.y }
-    pub fn size(&self) -> f64 { self.size }
-    pub fn life(&self) -> f64 { self.life }
-    pub fn color(&self) -> &str { &self.color }
-}
-
-pub struct Vine {
-    pub points: Vec<Point>,
-    pub x: f64,
-    pub y: f64,
-    pub angle: f64,
-    pub speed: f64,
-    pub turn_speed: f64,
-    pub max_length: f64,
-    pub is_grown: bool,
-    pub color: String,
-    pub line_width: f64,
-}
-
-impl Vine {
-    pub fn new(x: f64, y: f64, color: String, size: f64, max_length: f64, min_length: f64) -> Self {
-        Vine {
-            points: vec![Point { x, y }],
-            x, y,
-            angle: random() * PI * 2.0,
-            speed: random() * 2.0 + 0.5,
-            turn_speed: random() * 0.12 - 0.06,
-            max_length: random() * max_length + min_length,
-            is_grown: false,
-            color,
-            line_width: (random() * size * 0.5) + (size * 0.5),
-        }
-    }
-
-    pub fn update(&mut self, width: f64, height: f64) -> bool {
-        if self.is_grown { return false; }
-        self.angle += self.turn_speed;
-        self.x += self.angle.cos() * self.speed;
-        self.y += self.angle.sin() * self.speed;
-        if self.x < 0.0 || self.x > width || self.y < 0.0 || self.y > height {
-            self.is_grown = true;
-            return false;
-        }
-        self.points.push(Point { x: self.x, y: self.y });
-        if self.points.len() as f64 > self.max_length {
-            self.is_grown = true;
-            return false;
-        }
-        true
-    }
-}
-
-pub struct Lightning {
-    pub segments: Vec<Point>,
-    pub branches: Vec<Vec<Point>>,
-    pub color: String,
-    pub line_width: f64,
-    pub life: f64,
-    pub decay: f64,
-}
-
-impl Lightning {
-    pub fn new(start_x: f64, start_y: f64, end_x: f64, end_y: f64, color: String) -> Self {
-        let mut segments = Vec::new();
-        let mut branches = Vec::new();
-
-        // Generate main bolt with jagged segments
-        let segment_count = 15;
-        for i in 0..=segment_count {
-            let t = i as f64 / segment_count as f64;
-            let x = start_x + (end_x - start_x) * t;
-            let y = start_y + (end_y - start_y) * t;
-
-            // Add perpendicular offset for jagged effect
-            let offset = (random() - 0.5) * 30.0;
-            let dx = end_y - start_y;
-            let dy = -(end_x - start_x);
-            let len = (dx * dx + dy * dy).sqrt();
-            let nx = dx / len;
-            let ny = dy / len;
-
-            segments.push(Point {
-                x: x + nx * offset,
-                y: y + ny * offset,
-            });
-        }
-
-        // Generate 2-4 branches from random points
-        let branch_count = (random() * 3.0).floor() as usize + 2;
-        for _ in 0..branch_count {
-            let branch_start_idx = (random() * (segments.len() as f64 * 0.7)).floor() as usize + 1;
-            if branch_start_idx >= segments.len() {
-                continue;
-            }
-
-            let start_point = &segments[branch_start_idx];
-            let branch_length = 5 + (random() * 5.0).floor() as usize;
-            let mut branch = Vec::new();
-            branch.push(start_point.clone());
-
-            let branch_angle = random() * PI * 2.0;
-            for i in 1..=branch_length {
-                let dist = i as f64 * 8.0;
-                let offset = (random() - 0.5) * 15.0;
-                branch.push(Point {
-                    x: start_point.x + branch_angle.cos() * dist + offset,
-                    y: start_point.y + branch_angle.sin() * dist + offset,
-                });
-            }
-            branches.push(branch);
-        }
-
-        Lightning {
-            segments,
-            branches,
-            color,
-            line_width: random() * 2.0 + 1.5,
-            life: 1.0,
-            decay: 0.02,
-        }
-    }
-
-    pub fn update(&mut self) -> bool {
-        self.life -= self.decay;
-        self.life > 0.0
-    }
-}
-
-pub struct DigitalBloom {
-    particles: Vec<Particle>,
-    vines: Vec<Vine>,
-    lightnings: Vec<Lightning>,
-    colors: Vec<String>,
-    max_particles: usize,
-}
-
-impl DigitalBloom {
-    pub fn new() -> Self {
-        DigitalBloom {
-            particles: Vec::new(),
-            vines: Vec::new(),
-            lightnings: Vec::new(),
-            colors: vec![
-                "#ff69b4".to_string(), "#00ffff".to_string(), "#7fff00".to_string(),
-                "#ff00ff".to_string(), "#ff8c00".to_string(), "#adff2f".to_string(),
-                "#d8bfd8".to_string(),
-            ],
-            max_particles: 800, // Increased for high-quality rendering with vine limiting
-        }
-    }
-
-    pub fn update(&mut self, width: f64, height: f64) {
-        // Calculate total particle count for dynamic decay
-        let total_particle_count = self.particles.len() +
-            self.vines.iter().map(|v| v.points.len()).sum::<usize>() +
-            self.lightnings.iter().map(|l| l.segments.len()).sum::<usize>();
-
-        // Update vines with dynamic fade speed
-        self.vines.retain_mut(|v| v.update(width, height));
-
-        // Update particles with dynamic decay based on total load
-        self.particles.retain_mut(|p| p.update(width, height, total_particle_count));
-
-        // Update lightning
-        self.lightnings.retain_mut(|l| l.update());
-    }
-
-    pub fn create_vine(&mut self, x: f64, y: f64, size: f64) {
-        let color = self.colors[(random() * self.colors.len() as f64).floor() as usize].clone();
-        self.vines.push(Vine::new(x, y, color, size, 200.0, 50.0)); // Long, beautiful vines
-        // No hard limits - dynamic decay handles cleanup naturally
-    }
-
-    pub fn create_particles_gravity(&mut self, x: f64, y: f64, count: usize, size: f64) {
-        let color = self.colors[(random() * self.colors.len() as f64).floor() as usize].clone();
-        for _ in 0..count {
-            let vx = (random() - 0.5) * 4.0;
-            let vy = -random() * 5.0 - 2.0;
-            self.particles.push(Particle::new(x, y, vx, vy, color.clone(), size * 0.5, "gravity".to_string()));
-        }
-        self.limit_particles();
-    }
-
-    pub fn create_particles_bounce(&mut self, x: f64, y: f64, count: usize, size: f64) {
-        let color = self.colors[(random() * self.colors.len() as f64).floor() as usize].clone();
-        for _ in 0..count {
-            let vx = (random() - 0.5) * 8.0;
-            let vy = (random() - 0.5) * 8.0;
-            self.particles.push(Particle::new(x, y, vx, vy, color.clone(), size * 0.5, "bounce".to_string()));
-        }
-        self.limit_particles();
-    }
-
-    pub fn create_particles_burst(&mut self, x: f64, y: f64, count: usize, size: f64) {
-        let color = self.colors[(random() * self.colors.len() as f64).floor() as usize].clone();
-        for i in 0..count {
-            let angle = (PI * 2.0 / count as f64) * i as f64;
-            let speed = random() * 6.0 + 2.0;
-            let vx = angle.cos() * speed;
-            let vy = angle.sin() * speed;
-            self.particles.push(Particle::new(x, y, vx, vy, color.clone(), size * 0.5, "burst".to_string()));
-        }
-        self.limit_particles();
-    }
-
-    pub fn create_lightning(&mut self, x: f64, y: f64, _width: f64, height: f64) {
-        let end_x = x + (random() - 0.5) * 300.0;
-        let end_y = y + (random() * 0.6 + 0.2) * height * 0.5;
-        let color = if random() < 0.5 { "#ffffff".to_string() } else { "#00ffff".to_string() };
-        self.lightnings.push(Lightning::new(x, y, end_x, end_y, color));
-    }
-
-    pub fn create_particles_constellation(&mut self, x: f64, y: f64, count: usize, size: f64) {
-        let color = self.colors[(random() * self.colors.len() as f64).floor() as usize].clone();
-        for _ in 0..count {
-            let offset_x = (random() - 0.5) * 60.0;
-            let offset_y = (random() - 0.5) * 60.0;
-            let vx = (random() - 0.5) * 0.5;
-            let vy = (random() - 0.5) * 0.5;
-            let mut particle = Particle::new(x + offset_x, y + offset_y, vx, vy, color.clone(), size, "constellation".to_string());
-            particle.decay = 0.002;
-            self.particles.push(particle);
-        }
-        self.limit_particles();
-    }
-
-    pub fn create_particles_vortex(&mut self, x: f64, y: f64, count: usize, size: f64) {
-        let color = self.colors[(random() * self.colors.len() as f64).floor() as usize].clone();
-        for i in 0..count {
-            let angle = (PI * 2.0 / count as f64) * i as f64 + random() * 0.5;
-            let radius = random() * 80.0 + 40.0;
-            let particle_x = x + angle.cos() * radius;
-            let particle_y = y + angle.sin() * radius;
-            self.particles.push(Particle::new_vortex(particle_x, particle_y, x, y, angle, radius, color.clone(), size * 0.6));
-        }
-        self.limit_particles();
-    }
-
-    fn limit_particles(&mut self) {
-        // No hard limits - dynamic decay handles cleanup naturally
-        // Keep this function for API compatibility but make it a no-op
-    }
-
-    pub fn clear(&mut self) {
-        self.particles.clear();
-        self.vines.clear();
-        self.lightnings.clear();
-    }
-
-    pub fn particles_slice(&self) -> &[Particle] {
-        &self.particles
-    }
-
-    pub fn particles_len(&self) -> usize {
-        self.particles.len()
-    }
-
-    pub fn vines_slice(&self) -> &[Vine] {
-        &self.vines
-    }
-
-    pub fn lightnings_slice(&self) -> &[Lightning] {
-        &self.lightnings
-    }
-}
+use digital_bloom_core::{BudgetPolicy, DigitalBloom, EngineConfig, Link, Palette, Particle, ParticleMode, Recording, Rgba, SvgOptions, Vine, LINK_WIDTH};
 
 // ==================== OPAQUE POINTER TYPES ====================
 
@@ -395,11 +25,15 @@ pub struct OpaqueDigitalBloom {
 pub struct CParticle {
     pub x: f64,
     pub y: f64,
+    /// Position before the last step; draw at prev + (x - prev) * alpha
+    pub prev_x: f64,
+    pub prev_y: f64,
     pub size: f64,
     pub life: f64,
     pub color_r: u8,
     pub color_g: u8,
     pub color_b: u8,
+    pub color_a: u8,
 }
 
 /// C-compatible point representation
@@ -419,7 +53,10 @@ pub struct CVine {
     pub color_r: u8,
     pub color_g: u8,
     pub color_b: u8,
+    pub color_a: u8,
     pub line_width: f64,
+    /// 0.0 while growing; rises toward 1.0 as a grown vine fades out
+    pub fade_progress: f64,
 }
 
 /// C-compatible lightning representation
@@ -431,10 +68,87 @@ pub struct CLightning {
     pub color_r: u8,
     pub color_g: u8,
     pub color_b: u8,
+    pub color_a: u8,
     pub line_width: f64,
     pub life: f64,
 }
 
+/// One lightning branch polyline in the table filled by digital_bloom_get_lightning_branches()
+///
+/// The points live in the shared point buffer at [points_offset, points_offset + points_len).
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CLightningBranch {
+    /// Index of the parent bolt, in digital_bloom_get_lightning() order
+    pub lightning_index: usize,
+    pub points_offset: usize,
+    pub points_len: usize,
+}
+
+/// C-compatible constellation link: one line segment between two stars
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct CLink {
+    /// Indices of the two stars, in digital_bloom_get_particles() order
+    pub a: usize,
+    pub b: usize,
+    pub x1: f64,
+    pub y1: f64,
+    pub x2: f64,
+    pub y2: f64,
+    pub color_r: u8,
+    pub color_g: u8,
+    pub color_b: u8,
+    pub color_a: u8,
+    pub line_width: f64,
+    /// Opacity, fading with distance and with the dimmer star's life
+    pub alpha: f64,
+}
+
+/// C-compatible RGBA color
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CColor {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+/// C-compatible physics configuration (mirrors EngineConfig)
+///
+/// All rates are per fixed 1/60s step. Start from digital_bloom_default_config()
+/// and override the fields you want to tune.
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct DigitalBloomConfig {
+    pub friction: f64,
+    pub gravity: f64,
+    pub bounce_restitution: f64,
+    pub particle_decay: f64,
+    pub constellation_decay: f64,
+    pub vortex_decay: f64,
+    pub lightning_decay: f64,
+    pub vortex_angular_velocity: f64,
+    pub vortex_spiral_speed: f64,
+    pub lightning_segments: u32,
+    pub lightning_jitter: f64,
+    pub lightning_branch_jitter: f64,
+    pub vine_max_length: f64,
+    pub vine_min_length: f64,
+    pub smoke_decay: f64,
+    pub smoke_growth: f64,
+    pub smoke_curl_strength: f64,
+    pub boid_decay: f64,
+    pub boid_view_radius: f64,
+    pub boid_separation: f64,
+    pub boid_alignment: f64,
+    pub boid_cohesion: f64,
+    pub boid_max_speed: f64,
+    pub boid_pointer_pull: f64,
+    pub constellation_link_distance: f64,
+}
+
 /// Error codes for FFI operations
 #[repr(C)]
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
@@ -443,27 +157,74 @@ pub enum DigitalBloomError {
     NullPointer = 1,
     InvalidMode = 2,
     OutOfMemory = 3,
+    InvalidPalette = 4,
+    InvalidSnapshot = 5,
+    InvalidPolicy = 6,
+}
+
+/// Particle budget policies accepted by digital_bloom_set_budget_policy()
+#[repr(u8)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum DigitalBloomBudget {
+    /// No limit at all
+    None = 0,
+    /// Keep at most max_particles, dropping the oldest first
+    HardCap = 1,
+    /// No hard limit; decay speeds up as the scene fills (watch default)
+    DynamicDecay = 2,
+    /// Keep at most max_particles, dropping random ones
+    RandomCull = 3,
+    /// Keep at most max_particles, dropping the smallest first
+    SmallestFirst = 4,
 }
 
 // ==================== MEMORY MANAGEMENT ====================
 
 /// Create a new DigitalBloom engine
-/// 
+///
 /// Returns an opaque pointer that must be freed with digital_bloom_destroy()
 #[no_mangle]
 pub extern "C" fn digital_bloom_create() -> *mut OpaqueDigitalBloom {
-    let bloom = Box::new(DigitalBloom::new());
-    Box::into_raw(bloom) as *mut OpaqueDigitalBloom
+    into_opaque(DigitalBloom::new())
+}
+
+/// Create a new DigitalBloom engine with a fixed random seed
+///
+/// The same seed and the same sequence of calls always produce the same
+/// animation, which makes bug reports reproducible.
+/// Returns an opaque pointer that must be freed with digital_bloom_destroy()
+#[no_mangle]
+pub extern "C" fn digital_bloom_create_with_seed(seed: u64) -> *mut OpaqueDigitalBloom {
+    into_opaque(DigitalBloom::with_seed(seed))
+}
+
+/// Create a new DigitalBloom engine with custom physics
+///
+/// A null config uses the defaults.
+/// Returns an opaque pointer that must be freed with digital_bloom_destroy()
+///
+/// # Safety
+/// - config must be null or point to a valid DigitalBloomConfig
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_create_with_config(
+    config: *const DigitalBloomConfig
+) -> *mut OpaqueDigitalBloom {
+    let config = if config.is_null() {
+        EngineConfig::default()
+    } else {
+        unsafe { config_from_c(&*config) }
+    };
+    into_opaque(DigitalBloom::with_config(config))
 }
 
 /// Destroy a DigitalBloom engine and free its memory
-/// 
+///
 /// # Safety
 /// - ptr must be a valid pointer returned from digital_bloom_create()
 /// - ptr must not be used after this call
 /// - Calling this function multiple times with the same pointer is undefined behavior
 #[no_mangle]
-pub extern "C" fn digital_bloom_destroy(ptr: *mut OpaqueDigitalBloom) {
+pub unsafe extern "C" fn digital_bloom_destroy(ptr: *mut OpaqueDigitalBloom) {
     if ptr.is_null() {
         return;
     }
@@ -476,11 +237,11 @@ pub extern "C" fn digital_bloom_destroy(ptr: *mut OpaqueDigitalBloom) {
 // ==================== PHYSICS UPDATE ====================
 
 /// Update the physics simulation by one frame
-/// 
+///
 /// # Safety
 /// - ptr must be a valid pointer returned from digital_bloom_create()
 #[no_mangle]
-pub extern "C" fn digital_bloom_update(
+pub unsafe extern "C" fn digital_bloom_update(
     ptr: *mut OpaqueDigitalBloom,
     width: f64,
     height: f64
@@ -497,212 +258,825 @@ pub extern "C" fn digital_bloom_update(
     DigitalBloomError::Success
 }
 
-// ==================== PARTICLE CREATION ====================
-
-/// Create particles with specified mode
-/// 
-/// # Modes
-/// - 0: Vine
-/// - 1: Gravity
-/// - 2: Bounce
-/// - 3: Burst
-/// - 4: Lightning
-/// - 5: Constellation
-/// - 6: Vortex
-/// 
+/// Advance the simulation by `dt_seconds` of wall-clock time
+///
+/// Runs as many fixed 1/60s steps as fit and carries the remainder over, so
+/// the animation speed doesn't depend on the display refresh rate.
+/// Use digital_bloom_set_bounds() to tell the engine the canvas size.
+///
 /// # Safety
 /// - ptr must be a valid pointer returned from digital_bloom_create()
 #[no_mangle]
-pub extern "C" fn digital_bloom_create_particles(
+pub unsafe extern "C" fn digital_bloom_update_dt(
     ptr: *mut OpaqueDigitalBloom,
-    mode: u8,
-    x: f64,
-    y: f64,
-    count: usize,
-    size: f64
+    dt_seconds: f64
 ) -> DigitalBloomError {
     if ptr.is_null() {
         return DigitalBloomError::NullPointer;
     }
-    
+
     unsafe {
         let bloom = &mut *(ptr as *mut DigitalBloom);
-        
-        // Route to appropriate creation function based on mode
-        match mode {
-            0 => bloom.create_vine(x, y, size),
-            1 => bloom.create_particles_gravity(x, y, count, size),
-            2 => bloom.create_particles_bounce(x, y, count, size),
-            3 => bloom.create_particles_burst(x, y, count, size),
-            4 => bloom.create_lightning(x, y, 400.0, 400.0), // Fixed canvas size for watch
-            5 => bloom.create_particles_constellation(x, y, count, size),
-            6 => bloom.create_particles_vortex(x, y, count, size),
-            _ => return DigitalBloomError::InvalidMode,
-        }
+        bloom.update_dt(dt_seconds);
     }
-    
+
     DigitalBloomError::Success
 }
 
-// ==================== PARTICLE RETRIEVAL ====================
-
-/// Get the current number of active particles
+/// Set the canvas size used by digital_bloom_update_dt() and lightning creation
 ///
 /// # Safety
 /// - ptr must be a valid pointer returned from digital_bloom_create()
 #[no_mangle]
-pub extern "C" fn digital_bloom_get_particle_count(
-    ptr: *const OpaqueDigitalBloom
-) -> usize {
+pub unsafe extern "C" fn digital_bloom_set_bounds(
+    ptr: *mut OpaqueDigitalBloom,
+    width: f64,
+    height: f64
+) -> DigitalBloomError {
     if ptr.is_null() {
-        return 0;
+        return DigitalBloomError::NullPointer;
     }
 
     unsafe {
-        let bloom = &*(ptr as *const DigitalBloom);
-        bloom.particles_len()
+        let bloom = &mut *(ptr as *mut DigitalBloom);
+        bloom.set_bounds(width, height);
     }
+
+    DigitalBloomError::Success
 }
 
-/// Get the current number of active vines
+/// Draw boids toward (x, y) until digital_bloom_clear_pointer() is called
 ///
 /// # Safety
 /// - ptr must be a valid pointer returned from digital_bloom_create()
 #[no_mangle]
-pub extern "C" fn digital_bloom_get_vine_count(
-    ptr: *const OpaqueDigitalBloom
-) -> usize {
+pub unsafe extern "C" fn digital_bloom_set_pointer(
+    ptr: *mut OpaqueDigitalBloom,
+    x: f64,
+    y: f64
+) -> DigitalBloomError {
     if ptr.is_null() {
-        return 0;
+        return DigitalBloomError::NullPointer;
     }
 
     unsafe {
-        let bloom = &*(ptr as *const DigitalBloom);
-        bloom.vines_slice().len()
+        let bloom = &mut *(ptr as *mut DigitalBloom);
+        bloom.set_pointer(x, y);
     }
+
+    DigitalBloomError::Success
 }
 
-/// Get the current number of active lightning bolts
+/// Stop drawing boids toward the pointer
 ///
 /// # Safety
 /// - ptr must be a valid pointer returned from digital_bloom_create()
 #[no_mangle]
-pub extern "C" fn digital_bloom_get_lightning_count(
-    ptr: *const OpaqueDigitalBloom
-) -> usize {
+pub unsafe extern "C" fn digital_bloom_clear_pointer(
+    ptr: *mut OpaqueDigitalBloom
+) -> DigitalBloomError {
     if ptr.is_null() {
-        return 0;
+        return DigitalBloomError::NullPointer;
     }
 
     unsafe {
-        let bloom = &*(ptr as *const DigitalBloom);
-        bloom.lightnings_slice().len()
+        let bloom = &mut *(ptr as *mut DigitalBloom);
+        bloom.clear_pointer();
     }
+
+    DigitalBloomError::Success
 }
 
-/// Get ONLY real particles for rendering (excludes vine points and lightning)
+/// Get the interpolation alpha (0..1) left over by digital_bloom_update_dt()
 ///
 /// # Safety
 /// - ptr must be a valid pointer returned from digital_bloom_create()
-/// - out_buffer must point to an array of at least buffer_capacity elements
-/// - Returns the actual number of particles written to the buffer
 #[no_mangle]
-pub extern "C" fn digital_bloom_get_particles(
-    ptr: *const OpaqueDigitalBloom,
-    out_buffer: *mut CParticle,
-    buffer_capacity: usize
-) -> usize {
-    if ptr.is_null() || out_buffer.is_null() {
-        return 0;
+pub unsafe extern "C" fn digital_bloom_get_alpha(
+    ptr: *const OpaqueDigitalBloom
+) -> f64 {
+    if ptr.is_null() {
+        return 0.0;
     }
 
     unsafe {
         let bloom = &*(ptr as *const DigitalBloom);
+        bloom.alpha()
+    }
+}
 
-        // Return ONLY actual particles (not vine points or lightning segments)
-        let particles = bloom.particles_slice();
-        let count = particles.len().min(buffer_capacity);
+// ==================== CONFIGURATION ====================
 
-        for (i, particle) in particles.iter().enumerate().take(count) {
-            *out_buffer.add(i) = particle_to_c(particle);
-        }
+/// Get the default physics configuration
+#[no_mangle]
+pub extern "C" fn digital_bloom_default_config() -> DigitalBloomConfig {
+    config_to_c(&EngineConfig::default())
+}
 
-        count
+/// Replace the engine's physics configuration; takes effect on the next step
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+/// - config must point to a valid DigitalBloomConfig
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_set_config(
+    ptr: *mut OpaqueDigitalBloom,
+    config: *const DigitalBloomConfig
+) -> DigitalBloomError {
+    if ptr.is_null() || config.is_null() {
+        return DigitalBloomError::NullPointer;
     }
+
+    unsafe {
+        let bloom = &mut *(ptr as *mut DigitalBloom);
+        bloom.set_config(config_from_c(&*config));
+    }
+
+    DigitalBloomError::Success
 }
 
-/// Get vines for path rendering (much more efficient than rendering as particles)
+/// Read the engine's current physics configuration
 ///
 /// # Safety
 /// - ptr must be a valid pointer returned from digital_bloom_create()
-/// - out_vines must point to an array of at least buffer_capacity CVine elements
-/// - out_points must point to an array large enough to hold all vine points
-/// - Returns the actual number of vines written
-///
-/// # Memory Layout
-/// Each CVine contains a pointer into the out_points array
+/// - out_config must point to writable memory for one DigitalBloomConfig
 #[no_mangle]
-pub extern "C" fn digital_bloom_get_vines(
+pub unsafe extern "C" fn digital_bloom_get_config(
     ptr: *const OpaqueDigitalBloom,
-    out_vines: *mut CVine,
-    buffer_capacity: usize,
-    out_points: *mut CPoint,
-    points_capacity: usize
-) -> usize {
-    if ptr.is_null() || out_vines.is_null() || out_points.is_null() {
-        return 0;
+    out_config: *mut DigitalBloomConfig
+) -> DigitalBloomError {
+    if ptr.is_null() || out_config.is_null() {
+        return DigitalBloomError::NullPointer;
     }
 
     unsafe {
         let bloom = &*(ptr as *const DigitalBloom);
-        let vines = bloom.vines_slice();
+        *out_config = config_to_c(bloom.config());
+    }
 
-        let mut vines_written = 0;
-        let mut points_written = 0;
+    DigitalBloomError::Success
+}
 
-        for vine in vines.iter().take(buffer_capacity) {
-            // Check if we have space for all points of this vine
-            if points_written + vine.points.len() > points_capacity {
-                break;
-            }
+// ==================== COLOR PALETTES ====================
 
-            let (r, g, b) = parse_hex_color(&vine.color);
-            let points_start = points_written;
+/// Replace the palette new elements are colored from
+///
+/// `weights` may be null for a uniform pick; otherwise it must hold `len`
+/// non-negative relative weights. Returns `InvalidPalette` for an empty
+/// palette or bad weights.
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+/// - colors must point to `len` CColor values
+/// - weights must be null or point to `len` doubles
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_set_palette(
+    ptr: *mut OpaqueDigitalBloom,
+    colors: *const CColor,
+    weights: *const f64,
+    len: usize
+) -> DigitalBloomError {
+    if ptr.is_null() || (colors.is_null() && len > 0) {
+        return DigitalBloomError::NullPointer;
+    }
 
-            // Write vine points
-            for point in vine.points.iter() {
-                *out_points.add(points_written) = CPoint {
-                    x: point.x,
-                    y: point.y,
-                };
-                points_written += 1;
+    unsafe {
+        let bloom = &mut *(ptr as *mut DigitalBloom);
+        let colors: Vec<Rgba> = if len == 0 {
+            Vec::new()
+        } else {
+            std::slice::from_raw_parts(colors, len).iter().map(|c| Rgba::new(c.r, c.g, c.b, c.a)).collect()
+        };
+
+        let palette = if weights.is_null() {
+            Palette::new(colors)
+        } else {
+            Palette::with_weights(colors, std::slice::from_raw_parts(weights, len).to_vec())
+        };
+
+        match palette {
+            Ok(palette) => bloom.set_palette(palette),
+            Err(_) => return DigitalBloomError::InvalidPalette,
+        }
+    }
+
+    DigitalBloomError::Success
+}
+
+/// Switch to a built-in palette by name ("bloom", "sunset", "ocean", "forest", "mono")
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+/// - name must be a valid NUL-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_set_builtin_palette(
+    ptr: *mut OpaqueDigitalBloom,
+    name: *const c_char
+) -> DigitalBloomError {
+    if ptr.is_null() || name.is_null() {
+        return DigitalBloomError::NullPointer;
+    }
+
+    unsafe {
+        let bloom = &mut *(ptr as *mut DigitalBloom);
+        let palette = CStr::from_ptr(name).to_str().ok().and_then(|name| Palette::builtin(name).ok());
+        match palette {
+            Some(palette) => bloom.set_palette(palette),
+            None => return DigitalBloomError::InvalidPalette,
+        }
+    }
+
+    DigitalBloomError::Success
+}
+
+/// Get the number of colors in the current palette
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_get_palette_len(
+    ptr: *const OpaqueDigitalBloom
+) -> usize {
+    if ptr.is_null() {
+        return 0;
+    }
+
+    unsafe {
+        let bloom = &*(ptr as *const DigitalBloom);
+        bloom.palette().colors().len()
+    }
+}
+
+/// Copy the current palette into caller-provided buffers
+///
+/// Unweighted palettes report a weight of 1.0 for every color.
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+/// - out_colors must point to an array of at least capacity elements
+/// - out_weights must be null or point to an array of at least capacity elements
+/// - Returns the actual number of colors written
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_get_palette(
+    ptr: *const OpaqueDigitalBloom,
+    out_colors: *mut CColor,
+    out_weights: *mut f64,
+    capacity: usize
+) -> usize {
+    if ptr.is_null() || out_colors.is_null() {
+        return 0;
+    }
+
+    unsafe {
+        let bloom = &*(ptr as *const DigitalBloom);
+        let palette = bloom.palette();
+        let count = palette.colors().len().min(capacity);
+
+        for (i, color) in palette.colors().iter().enumerate().take(count) {
+            *out_colors.add(i) = color_to_c(*color);
+            if !out_weights.is_null() {
+                *out_weights.add(i) = palette.weights().map_or(1.0, |w| w[i]);
             }
+        }
 
-            // Write vine metadata pointing to its points
-            *out_vines.add(vines_written) = CVine {
-                points_ptr: out_points.add(points_start),
-                points_len: vine.points.len(),
-                color_r: r,
-                color_g: g,
-                color_b: b,
-                line_width: vine.line_width,
-            };
-            vines_written += 1;
+        count
+    }
+}
+
+// ==================== PARTICLE BUDGET ====================
+
+/// Choose how the particle count is kept in check
+///
+/// `policy` must be one of the `DigitalBloomBudget` values; `max_particles` is
+/// ignored by the uncapped policies.
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_set_budget_policy(
+    ptr: *mut OpaqueDigitalBloom,
+    policy: u8,
+    max_particles: usize
+) -> DigitalBloomError {
+    if ptr.is_null() {
+        return DigitalBloomError::NullPointer;
+    }
+    let Some(policy) = BudgetPolicy::from_raw(policy, max_particles) else {
+        return DigitalBloomError::InvalidPolicy;
+    };
+
+    unsafe {
+        let bloom = &mut *(ptr as *mut DigitalBloom);
+        bloom.set_budget_policy(policy);
+    }
+
+    DigitalBloomError::Success
+}
+
+/// Get the current budget policy as a `DigitalBloomBudget` value
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+/// - out_max_particles must be null or point to a valid usize; it receives the cap (0 if uncapped)
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_get_budget_policy(
+    ptr: *const OpaqueDigitalBloom,
+    out_max_particles: *mut usize
+) -> u8 {
+    if ptr.is_null() {
+        return 0;
+    }
+
+    unsafe {
+        let bloom = &*(ptr as *const DigitalBloom);
+        let (policy, max) = bloom.budget_policy().to_raw();
+        if !out_max_particles.is_null() {
+            *out_max_particles = max;
         }
+        policy
+    }
+}
+
+/// Total on-screen elements (particles plus every vine and lightning point)
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_get_scene_load(
+    ptr: *const OpaqueDigitalBloom
+) -> usize {
+    if ptr.is_null() {
+        return 0;
+    }
+
+    unsafe {
+        let bloom = &*(ptr as *const DigitalBloom);
+        bloom.scene_load()
+    }
+}
+
+// ==================== RANDOM SEED ====================
+
+/// Restart the engine's random sequence from `seed` without clearing the scene
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_set_seed(
+    ptr: *mut OpaqueDigitalBloom,
+    seed: u64
+) -> DigitalBloomError {
+    if ptr.is_null() {
+        return DigitalBloomError::NullPointer;
+    }
+
+    unsafe {
+        let bloom = &mut *(ptr as *mut DigitalBloom);
+        bloom.set_seed(seed);
+    }
+
+    DigitalBloomError::Success
+}
+
+/// Get the seed the engine was created with (or last reseeded with)
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_get_seed(
+    ptr: *const OpaqueDigitalBloom
+) -> u64 {
+    if ptr.is_null() {
+        return 0;
+    }
+
+    unsafe {
+        let bloom = &*(ptr as *const DigitalBloom);
+        bloom.seed()
+    }
+}
+
+// ==================== SNAPSHOTS ====================
+
+/// Serialize the full engine state into a caller-provided buffer
+///
+/// Always returns the snapshot size in bytes; the buffer is only written when
+/// capacity is large enough. Pass a null buffer to query the size first.
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+/// - out_buffer must be null or point to at least capacity bytes
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_snapshot(
+    ptr: *const OpaqueDigitalBloom,
+    out_buffer: *mut u8,
+    capacity: usize
+) -> usize {
+    if ptr.is_null() {
+        return 0;
+    }
+
+    unsafe {
+        let bloom = &*(ptr as *const DigitalBloom);
+        let bytes = bloom.snapshot();
+        if !out_buffer.is_null() && capacity >= bytes.len() {
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), out_buffer, bytes.len());
+        }
+        bytes.len()
+    }
+}
+
+/// Replace the engine state with a snapshot from digital_bloom_snapshot()
+///
+/// The engine is left untouched if the snapshot is rejected.
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+/// - data must point to at least len bytes
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_restore(
+    ptr: *mut OpaqueDigitalBloom,
+    data: *const u8,
+    len: usize
+) -> DigitalBloomError {
+    if ptr.is_null() || data.is_null() {
+        return DigitalBloomError::NullPointer;
+    }
+
+    unsafe {
+        let bloom = &mut *(ptr as *mut DigitalBloom);
+        let bytes = std::slice::from_raw_parts(data, len);
+        if bloom.restore(bytes).is_err() {
+            return DigitalBloomError::InvalidSnapshot;
+        }
+    }
+
+    DigitalBloomError::Success
+}
+
+/// Create a new DigitalBloom engine from a snapshot
+///
+/// Returns null if the snapshot is invalid, otherwise an opaque pointer that
+/// must be freed with digital_bloom_destroy()
+///
+/// # Safety
+/// - data must point to at least len bytes
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_create_from_snapshot(
+    data: *const u8,
+    len: usize
+) -> *mut OpaqueDigitalBloom {
+    if data.is_null() {
+        return std::ptr::null_mut();
+    }
+
+    let bytes = unsafe { std::slice::from_raw_parts(data, len) };
+    match DigitalBloom::from_snapshot(bytes) {
+        // The snapshot already carries the engine's limits, so skip into_opaque
+        Ok(bloom) => Box::into_raw(Box::new(bloom)) as *mut OpaqueDigitalBloom,
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+// ==================== SVG EXPORT ====================
+
+/// Export the current scene as a NUL-terminated SVG document
+///
+/// Always returns the buffer size needed, including the NUL; the buffer is only
+/// written when capacity is large enough. Pass a null buffer to query the size.
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+/// - out_buffer must be null or point to at least capacity bytes
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_to_svg(
+    ptr: *const OpaqueDigitalBloom,
+    width: f64,
+    height: f64,
+    glow: bool,
+    out_buffer: *mut c_char,
+    capacity: usize
+) -> usize {
+    if ptr.is_null() {
+        return 0;
+    }
+
+    unsafe {
+        let bloom = &*(ptr as *const DigitalBloom);
+        let options = SvgOptions { glow, ..SvgOptions::default() };
+        let svg = bloom.to_svg_with(width, height, &options);
+        let required = svg.len() + 1;
+        if !out_buffer.is_null() && capacity >= required {
+            std::ptr::copy_nonoverlapping(svg.as_ptr(), out_buffer as *mut u8, svg.len());
+            *out_buffer.add(svg.len()) = 0;
+        }
+        required
+    }
+}
+
+// ==================== RECORDING ====================
+
+/// Start logging every call made on the engine, discarding any previous log
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_start_recording(
+    ptr: *mut OpaqueDigitalBloom
+) -> DigitalBloomError {
+    if ptr.is_null() {
+        return DigitalBloomError::NullPointer;
+    }
+
+    unsafe {
+        let bloom = &mut *(ptr as *mut DigitalBloom);
+        bloom.start_recording();
+    }
+
+    DigitalBloomError::Success
+}
+
+/// Stop logging and discard the log; fetch it first with digital_bloom_get_recording()
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_stop_recording(
+    ptr: *mut OpaqueDigitalBloom
+) -> DigitalBloomError {
+    if ptr.is_null() {
+        return DigitalBloomError::NullPointer;
+    }
+
+    unsafe {
+        let bloom = &mut *(ptr as *mut DigitalBloom);
+        bloom.stop_recording();
+    }
+
+    DigitalBloomError::Success
+}
+
+/// Serialize the recording in progress into a caller-provided buffer
+///
+/// Returns the recording size in bytes (0 when not recording); the buffer is
+/// only written when capacity is large enough. Pass a null buffer to query the
+/// size first.
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+/// - out_buffer must be null or point to at least capacity bytes
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_get_recording(
+    ptr: *const OpaqueDigitalBloom,
+    out_buffer: *mut u8,
+    capacity: usize
+) -> usize {
+    if ptr.is_null() {
+        return 0;
+    }
+
+    unsafe {
+        let bloom = &*(ptr as *const DigitalBloom);
+        let Some(recording) = bloom.recording() else {
+            return 0;
+        };
+        let bytes = recording.to_bytes();
+        if !out_buffer.is_null() && capacity >= bytes.len() {
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), out_buffer, bytes.len());
+        }
+        bytes.len()
+    }
+}
+
+/// Create a new DigitalBloom engine by replaying a recording
+///
+/// Returns null if the recording is invalid, otherwise an opaque pointer that
+/// must be freed with digital_bloom_destroy()
+///
+/// # Safety
+/// - data must point to at least len bytes
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_create_from_recording(
+    data: *const u8,
+    len: usize
+) -> *mut OpaqueDigitalBloom {
+    if data.is_null() {
+        return std::ptr::null_mut();
+    }
+
+    let bytes = unsafe { std::slice::from_raw_parts(data, len) };
+    match Recording::from_bytes(bytes).and_then(|recording| recording.replay()) {
+        // The recording starts from a snapshot, so skip into_opaque
+        Ok(bloom) => Box::into_raw(Box::new(bloom)) as *mut OpaqueDigitalBloom,
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+// ==================== PARTICLE CREATION ====================
+
+/// Create particles with specified mode
+///
+/// `mode` must be one of the `ParticleMode` values; anything else returns
+/// `InvalidMode`. Lightning uses the current canvas size (see digital_bloom_set_bounds()).
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_create_particles(
+    ptr: *mut OpaqueDigitalBloom,
+    mode: u8,
+    x: f64,
+    y: f64,
+    count: usize,
+    size: f64
+) -> DigitalBloomError {
+    if ptr.is_null() {
+        return DigitalBloomError::NullPointer;
+    }
+
+    let Ok(mode) = ParticleMode::try_from(mode) else {
+        return DigitalBloomError::InvalidMode;
+    };
+
+    unsafe {
+        let bloom = &mut *(ptr as *mut DigitalBloom);
+        bloom.create(mode, x, y, count, size);
+    }
+
+    DigitalBloomError::Success
+}
+
+// ==================== PARTICLE RETRIEVAL ====================
+
+/// Get the current number of active particles
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_get_particle_count(
+    ptr: *const OpaqueDigitalBloom
+) -> usize {
+    if ptr.is_null() {
+        return 0;
+    }
+
+    unsafe {
+        let bloom = &*(ptr as *const DigitalBloom);
+        bloom.particles_len()
+    }
+}
+
+/// Get the current number of active vines
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_get_vine_count(
+    ptr: *const OpaqueDigitalBloom
+) -> usize {
+    if ptr.is_null() {
+        return 0;
+    }
+
+    unsafe {
+        let bloom = &*(ptr as *const DigitalBloom);
+        bloom.vines_slice().len()
+    }
+}
+
+/// Get the current number of grown vines that are fading out
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_get_grown_vine_count(
+    ptr: *const OpaqueDigitalBloom
+) -> usize {
+    if ptr.is_null() {
+        return 0;
+    }
+
+    unsafe {
+        let bloom = &*(ptr as *const DigitalBloom);
+        bloom.grown_vines_slice().len()
+    }
+}
+
+/// Get the current number of active lightning bolts
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_get_lightning_count(
+    ptr: *const OpaqueDigitalBloom
+) -> usize {
+    if ptr.is_null() {
+        return 0;
+    }
+
+    unsafe {
+        let bloom = &*(ptr as *const DigitalBloom);
+        bloom.lightnings_slice().len()
+    }
+}
+
+/// Get ONLY real particles for rendering (excludes vine points and lightning)
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+/// - out_buffer must point to an array of at least buffer_capacity elements
+/// - Returns the actual number of particles written to the buffer
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_get_particles(
+    ptr: *const OpaqueDigitalBloom,
+    out_buffer: *mut CParticle,
+    buffer_capacity: usize
+) -> usize {
+    if ptr.is_null() || out_buffer.is_null() {
+        return 0;
+    }
+
+    unsafe {
+        let bloom = &*(ptr as *const DigitalBloom);
+
+        // Return ONLY actual particles (not vine points or lightning segments)
+        let particles = bloom.particles();
+        let count = particles.len().min(buffer_capacity);
+
+        for (i, particle) in particles.iter().enumerate().take(count) {
+            *out_buffer.add(i) = particle_to_c(&particle);
+        }
+
+        count
+    }
+}
+
+/// Get vines for path rendering (much more efficient than rendering as particles)
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+/// - out_vines must point to an array of at least buffer_capacity CVine elements
+/// - out_points must point to an array large enough to hold all vine points
+/// - Returns the actual number of vines written
+///
+/// # Memory Layout
+/// Each CVine contains a pointer into the out_points array
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_get_vines(
+    ptr: *const OpaqueDigitalBloom,
+    out_vines: *mut CVine,
+    buffer_capacity: usize,
+    out_points: *mut CPoint,
+    points_capacity: usize
+) -> usize {
+    if ptr.is_null() || out_vines.is_null() || out_points.is_null() {
+        return 0;
+    }
+
+    unsafe {
+        let bloom = &*(ptr as *const DigitalBloom);
+        write_vines(bloom.vines_slice(), out_vines, buffer_capacity, out_points, points_capacity)
+    }
+}
+
+/// Get grown vines that are fading out, for path rendering
+///
+/// Draw them like active vines, fading with `fade_progress` if desired.
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+/// - out_vines must point to an array of at least buffer_capacity CVine elements
+/// - out_points must point to an array large enough to hold all vine points
+/// - Returns the actual number of vines written
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_get_grown_vines(
+    ptr: *const OpaqueDigitalBloom,
+    out_vines: *mut CVine,
+    buffer_capacity: usize,
+    out_points: *mut CPoint,
+    points_capacity: usize
+) -> usize {
+    if ptr.is_null() || out_vines.is_null() || out_points.is_null() {
+        return 0;
+    }
 
-        vines_written
+    unsafe {
+        let bloom = &*(ptr as *const DigitalBloom);
+        write_vines(bloom.grown_vines_slice(), out_vines, buffer_capacity, out_points, points_capacity)
     }
 }
 
 /// Get lightning bolts for path rendering
 ///
+/// Only the main bolt is written; fetch its branches with digital_bloom_get_lightning_branches().
+///
 /// # Safety
 /// - ptr must be a valid pointer returned from digital_bloom_create()
 /// - out_lightning must point to an array of at least buffer_capacity elements
 /// - out_segments must point to an array large enough to hold all segments
 /// - Returns the actual number of lightning bolts written
 #[no_mangle]
-pub extern "C" fn digital_bloom_get_lightning(
+pub unsafe extern "C" fn digital_bloom_get_lightning(
     ptr: *const OpaqueDigitalBloom,
     out_lightning: *mut CLightning,
     buffer_capacity: usize,
@@ -726,7 +1100,6 @@ pub extern "C" fn digital_bloom_get_lightning(
                 break;
             }
 
-            let (r, g, b) = parse_hex_color(&lightning.color);
             let segments_start = segments_written;
 
             // Write lightning segments
@@ -742,9 +1115,10 @@ pub extern "C" fn digital_bloom_get_lightning(
             *out_lightning.add(lightning_written) = CLightning {
                 segments_ptr: out_segments.add(segments_start),
                 segments_len: lightning.segments.len(),
-                color_r: r,
-                color_g: g,
-                color_b: b,
+                color_r: lightning.color.r,
+                color_g: lightning.color.g,
+                color_b: lightning.color.b,
+                color_a: lightning.color.a,
                 line_width: lightning.line_width,
                 life: lightning.life,
             };
@@ -755,14 +1129,198 @@ pub extern "C" fn digital_bloom_get_lightning(
     }
 }
 
+/// Get the total number of branches across all lightning bolts
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_get_lightning_branch_count(
+    ptr: *const OpaqueDigitalBloom
+) -> usize {
+    if ptr.is_null() {
+        return 0;
+    }
+
+    unsafe {
+        let bloom = &*(ptr as *const DigitalBloom);
+        bloom.lightnings_slice().iter().map(|l| l.branches.len()).sum()
+    }
+}
+
+/// Get lightning branches as a table of offsets into a shared point buffer
+///
+/// Branches are drawn with their parent bolt's color, width and life.
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+/// - out_branches must point to an array of at least branches_capacity elements
+/// - out_points must point to an array of at least points_capacity elements
+/// - Returns the actual number of branches written
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_get_lightning_branches(
+    ptr: *const OpaqueDigitalBloom,
+    out_branches: *mut CLightningBranch,
+    branches_capacity: usize,
+    out_points: *mut CPoint,
+    points_capacity: usize
+) -> usize {
+    if ptr.is_null() || out_branches.is_null() || out_points.is_null() {
+        return 0;
+    }
+
+    unsafe {
+        let bloom = &*(ptr as *const DigitalBloom);
+
+        let mut branches_written = 0;
+        let mut points_written = 0;
+
+        let branches = bloom
+            .lightnings_slice()
+            .iter()
+            .enumerate()
+            .flat_map(|(index, lightning)| lightning.branches.iter().map(move |branch| (index, branch)));
+
+        for (lightning_index, branch) in branches.take(branches_capacity) {
+            // Check if we have space for all points of this branch
+            if points_written + branch.len() > points_capacity {
+                break;
+            }
+
+            let points_offset = points_written;
+
+            for point in branch {
+                *out_points.add(points_written) = CPoint {
+                    x: point.x,
+                    y: point.y,
+                };
+                points_written += 1;
+            }
+
+            *out_branches.add(branches_written) = CLightningBranch {
+                lightning_index,
+                points_offset,
+                points_len: branch.len(),
+            };
+            branches_written += 1;
+        }
+
+        branches_written
+    }
+}
+
+// ==================== CONSTELLATION LINKS ====================
+
+/// Get the number of constellation links from the last update
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_get_link_count(
+    ptr: *const OpaqueDigitalBloom
+) -> usize {
+    if ptr.is_null() {
+        return 0;
+    }
+
+    unsafe {
+        let bloom = &*(ptr as *const DigitalBloom);
+        bloom.links().len()
+    }
+}
+
+/// Get the line segments joining nearby constellation stars
+///
+/// Draw them before the particles so the stars sit on top.
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+/// - out_buffer must point to an array of at least buffer_capacity elements
+/// - Returns the actual number of links written to the buffer
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_get_links(
+    ptr: *const OpaqueDigitalBloom,
+    out_buffer: *mut CLink,
+    buffer_capacity: usize
+) -> usize {
+    if ptr.is_null() || out_buffer.is_null() {
+        return 0;
+    }
+
+    unsafe {
+        let bloom = &*(ptr as *const DigitalBloom);
+        let links = bloom.links();
+        let count = links.len().min(buffer_capacity);
+
+        for (i, link) in links.iter().enumerate().take(count) {
+            *out_buffer.add(i) = link_to_c(link);
+        }
+
+        count
+    }
+}
+
+// ==================== NEIGHBOR QUERIES ====================
+
+/// Find the particles within radius of (x, y)
+///
+/// Indices are ascending and refer to the order of digital_bloom_get_particles().
+///
+/// # Safety
+/// - ptr must be a valid pointer returned from digital_bloom_create()
+/// - out_indices must point to an array of at least buffer_capacity elements,
+///   or be null to only count the matches
+/// - Returns the total number of matches; only the first buffer_capacity are written
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_query_radius(
+    ptr: *const OpaqueDigitalBloom,
+    x: f64,
+    y: f64,
+    radius: f64,
+    out_indices: *mut usize,
+    buffer_capacity: usize
+) -> usize {
+    if ptr.is_null() {
+        return 0;
+    }
+
+    unsafe {
+        let bloom = &*(ptr as *const DigitalBloom);
+        write_indices(&bloom.query_radius(x, y, radius), out_indices, buffer_capacity)
+    }
+}
+
+/// Find the particles inside a rectangle (edges included)
+///
+/// # Safety
+/// - Same contract as digital_bloom_query_radius()
+#[no_mangle]
+pub unsafe extern "C" fn digital_bloom_query_rect(
+    ptr: *const OpaqueDigitalBloom,
+    min_x: f64,
+    min_y: f64,
+    max_x: f64,
+    max_y: f64,
+    out_indices: *mut usize,
+    buffer_capacity: usize
+) -> usize {
+    if ptr.is_null() {
+        return 0;
+    }
+
+    unsafe {
+        let bloom = &*(ptr as *const DigitalBloom);
+        write_indices(&bloom.query_rect(min_x, min_y, max_x, max_y), out_indices, buffer_capacity)
+    }
+}
+
 // ==================== UTILITY FUNCTIONS ====================
 
 /// Clear all particles and reset the simulation
-/// 
+///
 /// # Safety
 /// - ptr must be a valid pointer returned from digital_bloom_create()
 #[no_mangle]
-pub extern "C" fn digital_bloom_clear(ptr: *mut OpaqueDigitalBloom) {
+pub unsafe extern "C" fn digital_bloom_clear(ptr: *mut OpaqueDigitalBloom) {
     if ptr.is_null() {
         return;
     }
@@ -775,38 +1333,183 @@ pub extern "C" fn digital_bloom_clear(ptr: *mut OpaqueDigitalBloom) {
 
 // ==================== HELPER FUNCTIONS ====================
 
+/// Apply watch defaults and hand ownership of the engine to C
+fn into_opaque(mut bloom: DigitalBloom) -> *mut OpaqueDigitalBloom {
+    // Watch relies on dynamic decay rather than a hard particle cap
+    bloom.set_budget_policy(BudgetPolicy::DynamicDecay);
+    Box::into_raw(Box::new(bloom)) as *mut OpaqueDigitalBloom
+}
+
+/// Copy vines and their points into caller buffers, stopping at whichever fills first
+///
+/// # Safety
+/// - out_vines must point to at least buffer_capacity elements
+/// - out_points must point to at least points_capacity elements
+unsafe fn write_vines(
+    vines: &[Vine],
+    out_vines: *mut CVine,
+    buffer_capacity: usize,
+    out_points: *mut CPoint,
+    points_capacity: usize
+) -> usize {
+    let mut vines_written = 0;
+    let mut points_written = 0;
+
+    for vine in vines.iter().take(buffer_capacity) {
+        // Check if we have space for all points of this vine
+        if points_written + vine.points().len() > points_capacity {
+            break;
+        }
+
+        let points_start = points_written;
+
+        unsafe {
+            // Write vine points
+            for point in vine.points().iter() {
+                *out_points.add(points_written) = CPoint {
+                    x: point.x,
+                    y: point.y,
+                };
+                points_written += 1;
+            }
+
+            // Write vine metadata pointing to its points
+            *out_vines.add(vines_written) = CVine {
+                points_ptr: out_points.add(points_start),
+                points_len: vine.points().len(),
+                color_r: vine.color.r,
+                color_g: vine.color.g,
+                color_b: vine.color.b,
+                color_a: vine.color.a,
+                line_width: vine.line_width,
+                fade_progress: vine.fade_progress(),
+            };
+        }
+        vines_written += 1;
+    }
+
+    vines_written
+}
+
+/// Copy up to buffer_capacity indices out and return how many there were in total
+///
+/// # Safety
+/// - out_indices must be null or point to at least buffer_capacity elements
+unsafe fn write_indices(found: &[usize], out_indices: *mut usize, buffer_capacity: usize) -> usize {
+    if !out_indices.is_null() {
+        for (i, &index) in found.iter().take(buffer_capacity).enumerate() {
+            unsafe { *out_indices.add(i) = index };
+        }
+    }
+    found.len()
+}
+
+/// Convert EngineConfig to its C mirror
+fn config_to_c(config: &EngineConfig) -> DigitalBloomConfig {
+    DigitalBloomConfig {
+        friction: config.friction,
+        gravity: config.gravity,
+        bounce_restitution: config.bounce_restitution,
+        particle_decay: config.particle_decay,
+        constellation_decay: config.constellation_decay,
+        vortex_decay: config.vortex_decay,
+        lightning_decay: config.lightning_decay,
+        vortex_angular_velocity: config.vortex_angular_velocity,
+        vortex_spiral_speed: config.vortex_spiral_speed,
+        lightning_segments: config.lightning_segments,
+        lightning_jitter: config.lightning_jitter,
+        lightning_branch_jitter: config.lightning_branch_jitter,
+        vine_max_length: config.vine_max_length,
+        vine_min_length: config.vine_min_length,
+        smoke_decay: config.smoke_decay,
+        smoke_growth: config.smoke_growth,
+        smoke_curl_strength: config.smoke_curl_strength,
+        boid_decay: config.boid_decay,
+        boid_view_radius: config.boid_view_radius,
+        boid_separation: config.boid_separation,
+        boid_alignment: config.boid_alignment,
+        boid_cohesion: config.boid_cohesion,
+        boid_max_speed: config.boid_max_speed,
+        boid_pointer_pull: config.boid_pointer_pull,
+        constellation_link_distance: config.constellation_link_distance,
+    }
+}
+
+/// Convert the C config mirror back to EngineConfig
+fn config_from_c(config: &DigitalBloomConfig) -> EngineConfig {
+    EngineConfig {
+        friction: config.friction,
+        gravity: config.gravity,
+        bounce_restitution: config.bounce_restitution,
+        particle_decay: config.particle_decay,
+        constellation_decay: config.constellation_decay,
+        vortex_decay: config.vortex_decay,
+        lightning_decay: config.lightning_decay,
+        vortex_angular_velocity: config.vortex_angular_velocity,
+        vortex_spiral_speed: config.vortex_spiral_speed,
+        lightning_segments: config.lightning_segments,
+        lightning_jitter: config.lightning_jitter,
+        lightning_branch_jitter: config.lightning_branch_jitter,
+        vine_max_length: config.vine_max_length,
+        vine_min_length: config.vine_min_length,
+        smoke_decay: config.smoke_decay,
+        smoke_growth: config.smoke_growth,
+        smoke_curl_strength: config.smoke_curl_strength,
+        boid_decay: config.boid_decay,
+        boid_view_radius: config.boid_view_radius,
+        boid_separation: config.boid_separation,
+        boid_alignment: config.boid_alignment,
+        boid_cohesion: config.boid_cohesion,
+        boid_max_speed: config.boid_max_speed,
+        boid_pointer_pull: config.boid_pointer_pull,
+        constellation_link_distance: config.constellation_link_distance,
+    }
+}
+
+/// Convert engine color to C-compatible CColor
+fn color_to_c(color: Rgba) -> CColor {
+    CColor {
+        r: color.r,
+        g: color.g,
+        b: color.b,
+        a: color.a,
+    }
+}
+
+/// Convert a constellation Link to C-compatible CLink
+fn link_to_c(link: &Link) -> CLink {
+    CLink {
+        a: link.a,
+        b: link.b,
+        x1: link.from.x,
+        y1: link.from.y,
+        x2: link.to.x,
+        y2: link.to.y,
+        color_r: link.color.r,
+        color_g: link.color.g,
+        color_b: link.color.b,
+        color_a: link.color.a,
+        line_width: LINK_WIDTH,
+        alpha: link.alpha,
+    }
+}
+
 /// Convert internal Particle to C-compatible CParticle
 fn particle_to_c(particle: &Particle) -> CParticle {
-    // Parse hex color string to RGB
-    let (r, g, b) = parse_hex_color(particle.color());
+    let color = particle.color();
 
     CParticle {
         x: particle.x(),
         y: particle.y(),
+        prev_x: particle.prev_x(),
+        prev_y: particle.prev_y(),
         size: particle.size() * particle.life(), // Scale size by life for fade effect
         life: particle.life(),
-        color_r: r,
-        color_g: g,
-        color_b: b,
-    }
-}
-
-/// Parse hex color string like "#ff00ff" to RGB tuple
-fn parse_hex_color(hex: &str) -> (u8, u8, u8) {
-    let hex = hex.trim_start_matches('#');
-    
-    if hex.len() == 6 {
-        if let (Ok(r), Ok(g), Ok(b)) = (
-            u8::from_str_radix(&hex[0..2], 16),
-            u8::from_str_radix(&hex[2..4], 16),
-            u8::from_str_radix(&hex[4..6], 16),
-        ) {
-            return (r, g, b);
-        }
+        color_r: color.r,
+        color_g: color.g,
+        color_b: color.b,
+        color_a: color.a,
     }
-    
-    // Fallback to white if parsing fails
-    (255, 255, 255)
 }
 
 // ==================== TESTS ====================
@@ -819,13 +1522,258 @@ mod tests {
     fn test_create_destroy() {
         let ptr = digital_bloom_create();
         assert!(!ptr.is_null());
-        digital_bloom_destroy(ptr);
+        unsafe { digital_bloom_destroy(ptr) };
     }
     
     #[test]
-    fn test_hex_color_parsing() {
-        assert_eq!(parse_hex_color("#ff00ff"), (255, 0, 255));
-        assert_eq!(parse_hex_color("#00ffff"), (0, 255, 255));
-        assert_eq!(parse_hex_color("ff0000"), (255, 0, 0));
+    fn test_create_particles_rejects_invalid_mode() {
+        let ptr = digital_bloom_create();
+        unsafe {
+            assert_eq!(digital_bloom_create_particles(ptr, ParticleMode::Burst as u8, 50.0, 50.0, 10, 4.0), DigitalBloomError::Success);
+            assert_eq!(digital_bloom_create_particles(ptr, ParticleMode::Fireworks as u8, 50.0, 50.0, 10, 4.0), DigitalBloomError::Success);
+            assert_eq!(digital_bloom_create_particles(ptr, ParticleMode::Smoke as u8, 50.0, 50.0, 5, 4.0), DigitalBloomError::Success);
+            assert_eq!(digital_bloom_create_particles(ptr, ParticleMode::Boids as u8, 50.0, 50.0, 6, 4.0), DigitalBloomError::Success);
+            assert_eq!(digital_bloom_create_particles(ptr, 10, 50.0, 50.0, 10, 4.0), DigitalBloomError::InvalidMode);
+            assert_eq!(digital_bloom_get_particle_count(ptr), 22);
+            digital_bloom_destroy(ptr);
+        }
+    }
+
+    #[test]
+    fn test_get_links() {
+        let ptr = digital_bloom_create_with_seed(8);
+        unsafe {
+            digital_bloom_create_particles(ptr, ParticleMode::Constellation as u8, 200.0, 200.0, 8, 4.0);
+            assert_eq!(digital_bloom_get_link_count(ptr), 0);
+            digital_bloom_update(ptr, 400.0, 400.0);
+
+            let count = digital_bloom_get_link_count(ptr);
+            assert!(count > 0);
+            let empty = CLink {
+                a: 0, b: 0, x1: 0.0, y1: 0.0, x2: 0.0, y2: 0.0,
+                color_r: 0, color_g: 0, color_b: 0, color_a: 0, line_width: 0.0, alpha: 0.0,
+            };
+            let mut links = vec![empty; count];
+            assert_eq!(digital_bloom_get_links(ptr, links.as_mut_ptr(), count), count);
+            for link in &links {
+                assert!(link.a < link.b && link.alpha > 0.0 && link.alpha <= 1.0);
+                assert_eq!(link.line_width, LINK_WIDTH);
+            }
+            assert_eq!(digital_bloom_get_links(ptr, links.as_mut_ptr(), 1), 1);
+            digital_bloom_destroy(ptr);
+        }
+    }
+
+    #[test]
+    fn test_query_radius() {
+        let ptr = digital_bloom_create_with_seed(3);
+        unsafe {
+            digital_bloom_create_particles(ptr, ParticleMode::Constellation as u8, 100.0, 100.0, 30, 4.0);
+            digital_bloom_update(ptr, 400.0, 400.0);
+
+            let total = digital_bloom_query_radius(ptr, 100.0, 100.0, 25.0, std::ptr::null_mut(), 0);
+            assert!(total > 1);
+            let mut indices = vec![usize::MAX; total + 1];
+            assert_eq!(digital_bloom_query_radius(ptr, 100.0, 100.0, 25.0, indices.as_mut_ptr(), 2), total);
+            assert!(indices[0] < indices[1] && indices[2] == usize::MAX);
+            assert_eq!(digital_bloom_query_rect(ptr, 0.0, 0.0, 400.0, 400.0, indices.as_mut_ptr(), 0), digital_bloom_get_particle_count(ptr));
+            assert_eq!(digital_bloom_query_radius(std::ptr::null(), 0.0, 0.0, 10.0, indices.as_mut_ptr(), 1), 0);
+            digital_bloom_destroy(ptr);
+        }
+    }
+
+    #[test]
+    fn test_create_with_seed() {
+        let ptr = digital_bloom_create_with_seed(1234);
+        unsafe {
+            assert_eq!(digital_bloom_get_seed(ptr), 1234);
+            assert_eq!(digital_bloom_set_seed(ptr, 99), DigitalBloomError::Success);
+            assert_eq!(digital_bloom_get_seed(ptr), 99);
+            digital_bloom_destroy(ptr);
+        }
+    }
+
+    #[test]
+    fn test_config_round_trip() {
+        let mut config = digital_bloom_default_config();
+        config.gravity = 0.6;
+        unsafe {
+            let ptr = digital_bloom_create_with_config(&config);
+            let mut out = digital_bloom_default_config();
+            assert_eq!(digital_bloom_get_config(ptr, &mut out), DigitalBloomError::Success);
+            assert_eq!(out.gravity, 0.6);
+            assert_eq!(digital_bloom_set_config(ptr, std::ptr::null()), DigitalBloomError::NullPointer);
+            digital_bloom_destroy(ptr);
+        }
+    }
+
+    #[test]
+    fn test_palette_round_trip() {
+        let colors = [CColor { r: 255, g: 0, b: 0, a: 255 }, CColor { r: 0, g: 0, b: 255, a: 128 }];
+        let weights = [1.0, 3.0];
+        let ptr = digital_bloom_create();
+        unsafe {
+            assert_eq!(digital_bloom_set_palette(ptr, colors.as_ptr(), weights.as_ptr(), 2), DigitalBloomError::Success);
+            assert_eq!(digital_bloom_get_palette_len(ptr), 2);
+
+            let mut out_colors = [CColor { r: 0, g: 0, b: 0, a: 0 }; 2];
+            let mut out_weights = [0.0; 2];
+            assert_eq!(digital_bloom_get_palette(ptr, out_colors.as_mut_ptr(), out_weights.as_mut_ptr(), 2), 2);
+            assert_eq!(out_colors, colors);
+            assert_eq!(out_weights, weights);
+
+            assert_eq!(digital_bloom_set_palette(ptr, colors.as_ptr(), std::ptr::null(), 0), DigitalBloomError::InvalidPalette);
+            assert_eq!(digital_bloom_set_builtin_palette(ptr, c"ocean".as_ptr()), DigitalBloomError::Success);
+            assert_eq!(digital_bloom_set_builtin_palette(ptr, c"nope".as_ptr()), DigitalBloomError::InvalidPalette);
+            digital_bloom_destroy(ptr);
+        }
+    }
+
+    #[test]
+    fn test_particle_colors_come_from_palette() {
+        let color = CColor { r: 10, g: 20, b: 30, a: 40 };
+        let ptr = digital_bloom_create();
+        unsafe {
+            digital_bloom_set_palette(ptr, &color, std::ptr::null(), 1);
+            digital_bloom_create_particles(ptr, ParticleMode::Gravity as u8, 50.0, 50.0, 3, 4.0);
+
+            let mut out = [std::mem::zeroed::<CParticle>(); 3];
+            assert_eq!(digital_bloom_get_particles(ptr, out.as_mut_ptr(), out.len()), 3);
+            assert!(out.iter().all(|p| (p.color_r, p.color_g, p.color_b, p.color_a) == (10, 20, 30, 40)));
+            digital_bloom_destroy(ptr);
+        }
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let ptr = digital_bloom_create_with_seed(7);
+        unsafe {
+            digital_bloom_create_particles(ptr, ParticleMode::Burst as u8, 50.0, 50.0, 10, 4.0);
+            digital_bloom_update(ptr, 200.0, 200.0);
+
+            let len = digital_bloom_snapshot(ptr, std::ptr::null_mut(), 0);
+            let mut buffer = vec![0u8; len];
+            assert_eq!(digital_bloom_snapshot(ptr, buffer.as_mut_ptr(), buffer.len()), len);
+
+            let copy = digital_bloom_create_from_snapshot(buffer.as_ptr(), buffer.len());
+            assert!(!copy.is_null());
+            assert_eq!(digital_bloom_get_particle_count(copy), 10);
+            assert_eq!(digital_bloom_get_seed(copy), 7);
+
+            digital_bloom_clear(ptr);
+            assert_eq!(digital_bloom_restore(ptr, buffer.as_ptr(), buffer.len()), DigitalBloomError::Success);
+            assert_eq!(digital_bloom_get_particle_count(ptr), 10);
+            assert_eq!(digital_bloom_restore(ptr, buffer.as_ptr(), 3), DigitalBloomError::InvalidSnapshot);
+            assert!(digital_bloom_create_from_snapshot(buffer.as_ptr(), 3).is_null());
+
+            digital_bloom_destroy(copy);
+            digital_bloom_destroy(ptr);
+        }
+    }
+
+    #[test]
+    fn test_recording_replays() {
+        let ptr = digital_bloom_create_with_seed(3);
+        unsafe {
+            assert_eq!(digital_bloom_get_recording(ptr, std::ptr::null_mut(), 0), 0);
+            digital_bloom_start_recording(ptr);
+            digital_bloom_create_particles(ptr, ParticleMode::Vortex as u8, 80.0, 80.0, 12, 4.0);
+            digital_bloom_update_dt(ptr, 0.05);
+
+            let len = digital_bloom_get_recording(ptr, std::ptr::null_mut(), 0);
+            let mut buffer = vec![0u8; len];
+            assert_eq!(digital_bloom_get_recording(ptr, buffer.as_mut_ptr(), buffer.len()), len);
+            digital_bloom_stop_recording(ptr);
+
+            let replayed = digital_bloom_create_from_recording(buffer.as_ptr(), buffer.len());
+            assert!(!replayed.is_null());
+            let mut a = [std::mem::zeroed::<CParticle>(); 12];
+            let mut b = [std::mem::zeroed::<CParticle>(); 12];
+            assert_eq!(digital_bloom_get_particles(ptr, a.as_mut_ptr(), 12), 12);
+            assert_eq!(digital_bloom_get_particles(replayed, b.as_mut_ptr(), 12), 12);
+            assert!(a.iter().zip(&b).all(|(a, b)| (a.x, a.y, a.life) == (b.x, b.y, b.life)));
+            assert!(digital_bloom_create_from_recording(buffer.as_ptr(), 4).is_null());
+
+            digital_bloom_destroy(replayed);
+            digital_bloom_destroy(ptr);
+        }
+    }
+
+    #[test]
+    fn test_budget_policy() {
+        let ptr = digital_bloom_create();
+        unsafe {
+            assert_eq!(digital_bloom_get_budget_policy(ptr, std::ptr::null_mut()), DigitalBloomBudget::DynamicDecay as u8);
+            digital_bloom_create_particles(ptr, ParticleMode::Burst as u8, 50.0, 50.0, 30, 4.0);
+            assert_eq!(digital_bloom_get_scene_load(ptr), 30);
+
+            assert_eq!(digital_bloom_set_budget_policy(ptr, DigitalBloomBudget::SmallestFirst as u8, 10), DigitalBloomError::Success);
+            assert_eq!(digital_bloom_get_particle_count(ptr), 10);
+            let mut max = 0;
+            assert_eq!(digital_bloom_get_budget_policy(ptr, &mut max), DigitalBloomBudget::SmallestFirst as u8);
+            assert_eq!(max, 10);
+            assert_eq!(digital_bloom_set_budget_policy(ptr, 9, 10), DigitalBloomError::InvalidPolicy);
+            digital_bloom_destroy(ptr);
+        }
+    }
+
+    #[test]
+    fn test_grown_vines_fade() {
+        let ptr = digital_bloom_create_with_seed(8);
+        unsafe {
+            digital_bloom_create_particles(ptr, ParticleMode::Vine as u8, 200.0, 200.0, 1, 4.0);
+            while digital_bloom_get_vine_count(ptr) > 0 {
+                digital_bloom_update(ptr, 400.0, 400.0);
+            }
+            digital_bloom_update(ptr, 400.0, 400.0);
+            assert_eq!(digital_bloom_get_grown_vine_count(ptr), 1);
+
+            let mut vines = [std::mem::zeroed::<CVine>(); 1];
+            let mut points = vec![CPoint { x: 0.0, y: 0.0 }; 1000];
+            assert_eq!(digital_bloom_get_grown_vines(ptr, vines.as_mut_ptr(), 1, points.as_mut_ptr(), points.len()), 1);
+            assert!(vines[0].fade_progress > 0.0 && vines[0].fade_progress < 1.0);
+
+            while digital_bloom_get_grown_vine_count(ptr) > 0 {
+                digital_bloom_update(ptr, 400.0, 400.0);
+            }
+            digital_bloom_destroy(ptr);
+        }
+    }
+
+    #[test]
+    fn test_lightning_branches() {
+        let ptr = digital_bloom_create_with_seed(6);
+        unsafe {
+            digital_bloom_create_particles(ptr, ParticleMode::Lightning as u8, 100.0, 50.0, 1, 4.0);
+            digital_bloom_create_particles(ptr, ParticleMode::Lightning as u8, 300.0, 50.0, 1, 4.0);
+            let count = digital_bloom_get_lightning_branch_count(ptr);
+            assert!(count >= 4);
+
+            let mut branches = vec![CLightningBranch { lightning_index: 0, points_offset: 0, points_len: 0 }; count];
+            let mut points = vec![CPoint { x: 0.0, y: 0.0 }; 1000];
+            assert_eq!(digital_bloom_get_lightning_branches(ptr, branches.as_mut_ptr(), count, points.as_mut_ptr(), points.len()), count);
+            assert_eq!(branches[0].points_offset, 0);
+            assert_eq!(branches.last().unwrap().lightning_index, 1);
+            for pair in branches.windows(2) {
+                assert_eq!(pair[1].points_offset, pair[0].points_offset + pair[0].points_len);
+            }
+            digital_bloom_destroy(ptr);
+        }
+    }
+
+    #[test]
+    fn test_to_svg() {
+        let ptr = digital_bloom_create_with_seed(2);
+        unsafe {
+            digital_bloom_create_particles(ptr, ParticleMode::Burst as u8, 50.0, 50.0, 4, 4.0);
+            let len = digital_bloom_to_svg(ptr, 200.0, 200.0, false, std::ptr::null_mut(), 0);
+            let mut buffer = vec![1 as c_char; len];
+            assert_eq!(digital_bloom_to_svg(ptr, 200.0, 200.0, false, buffer.as_mut_ptr(), len), len);
+            let svg = CStr::from_ptr(buffer.as_ptr()).to_str().unwrap();
+            assert_eq!(svg.len() + 1, len);
+            assert_eq!(svg.matches("<circle").count(), 4);
+            assert!(!svg.contains("<filter"));
+            digital_bloom_destroy(ptr);
+        }
     }
 }
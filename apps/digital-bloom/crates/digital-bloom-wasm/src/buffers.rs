@@ -0,0 +1,124 @@
+//! Packed render buffers that JS reads as typed-array views into wasm memory
+//!
+//! `DigitalBloom::pack_render_buffers` refills these once per frame, reusing
+//! the allocations, so drawing a frame allocates nothing on either side.
+//!
+//! Layouts (all values are what the JS getters report, e.g. sizes already
+//! scaled by life):
+//! - particles: `PARTICLE_STRIDE` f32 per particle: x, y, prev_x, prev_y, size, life
+//! - particle colors: 4 u8 (r, g, b, a) per particle
+//! - path points: 2 f32 (x, y) per point, shared by every path
+//! - paths: `PATH_STRIDE` u32 per path: point offset, point count, `PathKind`
+//! - path styles: 2 f32 per path: line width, life (1 - fade progress for vines)
+//! - path colors: 4 u8 (r, g, b, a) per path
+
+use digital_bloom_core::{DigitalBloom, Point, Rgba};
+
+pub const PARTICLE_STRIDE: usize = 6;
+pub const PATH_STRIDE: usize = 3;
+
+/// Path kinds stored in the third slot of each path entry
+#[repr(u32)]
+#[derive(Copy, Clone)]
+pub enum PathKind {
+    Vine = 0,
+    GrownVine = 1,
+    Lightning = 2,
+    LightningBranch = 3,
+}
+
+#[derive(Default)]
+pub struct RenderBuffers {
+    pub particles: Vec<f32>,
+    pub particle_colors: Vec<u8>,
+    pub path_points: Vec<f32>,
+    pub paths: Vec<u32>,
+    pub path_styles: Vec<f32>,
+    pub path_colors: Vec<u8>,
+}
+
+impl RenderBuffers {
+    /// Repack everything from the engine's current state
+    pub fn fill(&mut self, engine: &DigitalBloom) {
+        self.particles.clear();
+        self.particle_colors.clear();
+        self.path_points.clear();
+        self.paths.clear();
+        self.path_styles.clear();
+        self.path_colors.clear();
+
+        for p in engine.particles().iter() {
+            self.particles.extend_from_slice(&[
+                p.x() as f32,
+                p.y() as f32,
+                p.prev_x() as f32,
+                p.prev_y() as f32,
+                (p.size() * p.life()) as f32,
+                p.life() as f32,
+            ]);
+            push_color(&mut self.particle_colors, p.color());
+        }
+
+        for vine in engine.vines_slice() {
+            self.push_path(vine.points(), PathKind::Vine, vine.line_width, 1.0, vine.color);
+        }
+        for vine in engine.grown_vines_slice() {
+            self.push_path(vine.points(), PathKind::GrownVine, vine.line_width, 1.0 - vine.fade_progress(), vine.color);
+        }
+        for lightning in engine.lightnings_slice() {
+            let line_width = lightning.line_width * lightning.life;
+            self.push_path(&lightning.segments, PathKind::Lightning, line_width, lightning.life, lightning.color);
+            for branch in &lightning.branches {
+                self.push_path(branch, PathKind::LightningBranch, line_width, lightning.life, lightning.color);
+            }
+        }
+    }
+
+    fn push_path(&mut self, points: &[Point], kind: PathKind, line_width: f64, life: f64, color: Rgba) {
+        let offset = self.path_points.len() / 2;
+        self.paths.extend_from_slice(&[offset as u32, points.len() as u32, kind as u32]);
+        for point in points {
+            self.path_points.extend_from_slice(&[point.x as f32, point.y as f32]);
+        }
+        self.path_styles.extend_from_slice(&[line_width as f32, life as f32]);
+        push_color(&mut self.path_colors, color);
+    }
+}
+
+fn push_color(out: &mut Vec<u8>, color: Rgba) {
+    out.extend_from_slice(&[color.r, color.g, color.b, color.a]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_packs_particles_and_paths() {
+        let mut engine = DigitalBloom::with_seed(9);
+        engine.create_particles_burst(100.0, 100.0, 4, 8.0);
+        engine.create_vine(200.0, 200.0, 4.0);
+        engine.create_lightning(150.0, 50.0, 400.0, 400.0);
+
+        let mut buffers = RenderBuffers::default();
+        buffers.fill(&engine);
+        assert_eq!(buffers.particles.len(), 4 * PARTICLE_STRIDE);
+        assert_eq!(buffers.particle_colors.len(), 4 * 4);
+
+        let lightning = &engine.lightnings_slice()[0];
+        let path_count = 2 + lightning.branches.len();
+        assert_eq!(buffers.paths.len(), path_count * PATH_STRIDE);
+        assert_eq!(buffers.path_styles.len(), path_count * 2);
+        assert_eq!(buffers.path_colors.len(), path_count * 4);
+
+        // Paths tile the shared point buffer in order
+        let last = &buffers.paths[buffers.paths.len() - PATH_STRIDE..];
+        assert_eq!((last[0] + last[1]) as usize * 2, buffers.path_points.len());
+        assert_eq!(last[2], PathKind::LightningBranch as u32);
+
+        // Refilling reuses the same allocations
+        let capacity = buffers.particles.capacity();
+        buffers.fill(&engine);
+        assert_eq!(buffers.particles.capacity(), capacity);
+    }
+}
@@ -1,577 +1,458 @@
+//! Digital Bloom WASM bindings for the web app
+//!
+//! Thin wasm-bindgen wrapper around `digital-bloom-core`. All simulation logic
+//! lives in the core crate; this layer only converts to JS values.
+
+mod buffers;
 
 use wasm_bindgen::prelude::*;
-use serde::{Serialize};
-use std::f64::consts::PI;
+use serde::Serialize;
+use buffers::{RenderBuffers, PARTICLE_STRIDE, PATH_STRIDE};
+use digital_bloom_core::{BudgetPolicy, EngineConfig, Lightning, Link, Palette, Particle, Point, Recording, Rgba, SvgOptions, Vine, BUILTIN_PALETTES, LINK_WIDTH};
 
-// A simple random number generator
-fn random() -> f64 {
-    js_sys::Math::random()
-}
-
-#[derive(Clone, Serialize)]
-pub struct Point {
-    pub x: f64,
-    pub y: f64,
-}
+// Exported to TypeScript as the `ParticleMode` enum
+pub use digital_bloom_core::ParticleMode;
 
 #[derive(Serialize)]
-pub struct JsVine {
-    points: Vec<Point>,
-    color: String,
+pub struct JsVine<'a> {
+    points: &'a [Point],
+    color: Rgba,
     line_width: f64,
+    fade_progress: f64,
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize)]
 pub struct JsParticle {
     x: f64,
     y: f64,
-    color: String,
+    prev_x: f64,
+    prev_y: f64,
+    color: Rgba,
     size: f64,
     life: f64,
 }
 
 #[derive(Serialize)]
-pub struct JsLightning {
-    segments: Vec<Point>,
-    branches: Vec<Vec<Point>>,
-    color: String,
+pub struct JsLightning<'a> {
+    segments: &'a [Point],
+    branches: &'a [Vec<Point>],
+    color: Rgba,
     line_width: f64,
     life: f64,
 }
 
-pub struct Particle {
-    x: f64,
-    y: f64,
-    vx: f64,
-    vy: f64,
-    color: String,
-    size: f64,
-    mode: String,
-    life: f64,
-    decay: f64,
-    // Vortex-specific fields
-    origin_x: f64,
-    origin_y: f64,
-    angle: f64,
-    radius: f64,
+#[derive(Serialize)]
+pub struct JsLink {
+    a: usize,
+    b: usize,
+    x1: f64,
+    y1: f64,
+    x2: f64,
+    y2: f64,
+    color: Rgba,
+    line_width: f64,
+    alpha: f64,
 }
 
-impl Particle {
-    pub fn new(x: f64, y: f64, vx: f64, vy: f64, color: String, size: f64, mode: String) -> Self {
-        Particle {
-            x,
-            y,
-            vx,
-            vy,
-            color,
-            size,
-            mode,
-            life: 1.0,
-            decay: 0.005,
-            origin_x: x,
-            origin_y: y,
-            angle: 0.0,
-            radius: 0.0,
-        }
+fn vine_to_js(vine: &Vine) -> JsVine<'_> {
+    JsVine {
+        points: vine.points(),
+        color: vine.color,
+        line_width: vine.line_width,
+        fade_progress: vine.fade_progress(),
     }
+}
 
-    // FFI-friendly getter methods
-    pub fn x(&self) -> f64 { self.x }
-    pub fn y(&self) -> f64 { self.y }
-    pub fn size(&self) -> f64 { self.size }
-    pub fn life(&self) -> f64 { self.life }
-    pub fn color(&self) -> &str { &self.color }
-
-    pub fn new_vortex(x: f64, y: f64, origin_x: f64, origin_y: f64, angle: f64, radius: f64, color: String, size: f64) -> Self {
-        let vx = angle.cos() * 2.0;
-        let vy = angle.sin() * 2.0;
-        Particle {
-            x,
-            y,
-            vx,
-            vy,
-            color,
-            size,
-            mode: "vortex".to_string(),
-            life: 1.0,
-            decay: 0.003,
-            origin_x,
-            origin_y,
-            angle,
-            radius,
-        }
+fn particle_to_js(particle: &Particle) -> JsParticle {
+    JsParticle {
+        x: particle.x(),
+        y: particle.y(),
+        prev_x: particle.prev_x(),
+        prev_y: particle.prev_y(),
+        color: particle.color(),
+        size: particle.size() * particle.life(),
+        life: particle.life(),
     }
+}
 
-    pub fn update(&mut self, width: f64, height: f64) -> bool {
-        if self.mode == "vortex" {
-            // Vortex orbital physics
-            self.angle += 0.08; // Angular velocity
-            self.radius -= 0.5; // Spiral inward
-
-            if self.radius < 0.0 {
-                self.radius = 0.0;
-            }
-
-            // Update position based on orbital motion
-            self.x = self.origin_x + self.angle.cos() * self.radius;
-            self.y = self.origin_y + self.angle.sin() * self.radius;
-        } else {
-            // Apply friction
-            self.vx *= 0.99;
-            self.vy *= 0.99;
-
-            // Apply gravity for gravity mode
-            if self.mode == "gravity" {
-                self.vy += 0.3;
-            }
-
-            // Update position
-            self.x += self.vx;
-            self.y += self.vy;
-
-            // Bounce mode - wall collision
-            if self.mode == "bounce" {
-                let bounce = 0.7;
-                if self.x < self.size || self.x > width - self.size {
-                    self.vx *= -bounce;
-                    self.x = self.x.max(self.size).min(width - self.size);
-                }
-                if self.y < self.size || self.y > height - self.size {
-                    self.vy *= -bounce;
-                    self.y = self.y.max(self.size).min(height - self.size);
-                }
-            }
-        }
-
-        // Decay life
-        self.life -= self.decay;
-
-        // Check if particle is still alive and in bounds
-        self.life > 0.0 && self.x > -50.0 && self.x < width + 50.0 && self.y > -50.0 && self.y < height + 50.0
+fn link_to_js(link: &Link) -> JsLink {
+    JsLink {
+        a: link.a,
+        b: link.b,
+        x1: link.from.x,
+        y1: link.from.y,
+        x2: link.to.x,
+        y2: link.to.y,
+        color: link.color,
+        line_width: LINK_WIDTH,
+        alpha: link.alpha,
     }
+}
 
-    pub fn to_js(&self) -> JsParticle {
-        JsParticle {
-            x: self.x,
-            y: self.y,
-            color: self.color.clone(),
-            size: self.size * self.life,
-            life: self.life,
-        }
+fn lightning_to_js(lightning: &Lightning) -> JsLightning<'_> {
+    JsLightning {
+        segments: &lightning.segments,
+        branches: &lightning.branches,
+        color: lightning.color,
+        line_width: lightning.line_width * lightning.life,
+        life: lightning.life,
     }
 }
 
 #[wasm_bindgen]
-pub struct Vine {
-    points: Vec<Point>,
-    x: f64,
-    y: f64,
-    angle: f64,
-    speed: f64,
-    turn_speed: f64,
-    max_length: f64,
-    is_grown: bool,
-    color: String,
-    line_width: f64,
-    active: bool,
+pub struct DigitalBloom {
+    engine: digital_bloom_core::DigitalBloom,
+    buffers: RenderBuffers,
 }
 
-#[wasm_bindgen]
-impl Vine {
-    #[wasm_bindgen(constructor)]
-    pub fn new() -> Vine {
-        Vine {
-            points: Vec::new(),
-            x: 0.0,
-            y: 0.0,
-            angle: 0.0,
-            speed: 0.0,
-            turn_speed: 0.0,
-            max_length: 0.0,
-            is_grown: false,
-            color: "#ffffff".to_string(),
-            line_width: 1.0,
-            active: false,
+impl Default for DigitalBloom {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<digital_bloom_core::DigitalBloom> for DigitalBloom {
+    fn from(engine: digital_bloom_core::DigitalBloom) -> Self {
+        DigitalBloom {
+            engine,
+            buffers: RenderBuffers::default(),
         }
     }
+}
 
-    pub fn init(&mut self, x: f64, y: f64, color: String, size: f64, max_length: f64, min_length: f64) {
-        self.x = x;
-        self.y = y;
-        self.points = vec![Point { x: self.x, y: self.y }];
-        self.angle = random() * PI * 2.0;
-        self.speed = random() * 2.0 + 0.5;
-        self.turn_speed = random() * 0.12 - 0.06;
-        self.max_length = random() * max_length + min_length;
-        self.is_grown = false;
-        self.color = color;
-        self.line_width = (random() * size * 0.5) + (size * 0.5);
-        self.active = true;
+#[wasm_bindgen]
+impl DigitalBloom {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> DigitalBloom {
+        // std has no entropy source on wasm32, so seed from the browser
+        let seed = (js_sys::Math::random() * u64::MAX as f64) as u64;
+        DigitalBloom::with_seed(seed)
     }
 
-    pub fn update(&mut self, width: f64, height: f64) -> bool {
-        if self.is_grown || !self.active {
-            return false;
-        }
+    /// Create an engine whose animation is fully determined by `seed`
+    pub fn with_seed(seed: u64) -> DigitalBloom {
+        digital_bloom_core::DigitalBloom::with_seed(seed).into()
+    }
 
-        self.angle += self.turn_speed;
-        self.x += self.angle.cos() * self.speed;
-        self.y += self.angle.sin() * self.speed;
+    /// Create an engine with custom physics
+    ///
+    /// `config` is a plain object with any subset of the `EngineConfig` fields;
+    /// missing fields use the defaults.
+    pub fn with_config(config: JsValue) -> Result<DigitalBloom, JsValue> {
+        let mut bloom = DigitalBloom::new();
+        bloom.set_config(config)?;
+        Ok(bloom)
+    }
 
-        if self.x < 0.0 || self.x > width || self.y < 0.0 || self.y > height {
-            self.is_grown = true;
-            return false;
-        }
+    /// Replace the physics parameters; missing fields use the defaults
+    pub fn set_config(&mut self, config: JsValue) -> Result<(), JsValue> {
+        let config: EngineConfig = serde_wasm_bindgen::from_value(config)?;
+        self.engine.set_config(config);
+        Ok(())
+    }
 
-        self.points.push(Point { x: self.x, y: self.y });
+    #[wasm_bindgen(getter)]
+    pub fn config(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(self.engine.config()).unwrap()
+    }
 
-        if self.points.len() as f64 > self.max_length {
-            self.is_grown = true;
-            return false;
-        }
+    /// Replace the palette new elements are colored from
+    ///
+    /// `palette` is `{ colors: ["#ff69b4", "#00ffff80", ...], weights?: [1, 2, ...] }`.
+    pub fn set_palette(&mut self, palette: JsValue) -> Result<(), JsValue> {
+        let palette: Palette = serde_wasm_bindgen::from_value(palette)?;
+        self.engine.set_palette(palette);
+        Ok(())
+    }
 
-        true
+    #[wasm_bindgen(getter)]
+    pub fn palette(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(self.engine.palette()).unwrap()
     }
 
-    pub fn fade(&mut self) -> bool {
-        if !self.active || self.points.is_empty() {
-            return false;
-        }
-        self.points.remove(0);
-        self.points.len() > 1
+    /// Switch to one of the palettes listed by `builtin_palettes`
+    pub fn set_builtin_palette(&mut self, name: &str) -> Result<(), JsValue> {
+        let palette = Palette::builtin(name).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        self.engine.set_palette(palette);
+        Ok(())
     }
 
-    #[wasm_bindgen(getter)]
-    pub fn points(&self) -> JsValue {
-        serde_wasm_bindgen::to_value(&self.points).unwrap()
+    /// Names accepted by `set_builtin_palette`
+    pub fn builtin_palettes() -> Vec<String> {
+        BUILTIN_PALETTES.iter().map(|name| name.to_string()).collect()
     }
 
-    #[wasm_bindgen(getter)]
-    pub fn color(&self) -> String {
-        self.color.clone()
+    /// Restart the random sequence from `seed` without clearing the scene
+    pub fn set_seed(&mut self, seed: u64) {
+        self.engine.set_seed(seed);
     }
 
     #[wasm_bindgen(getter)]
-    pub fn line_width(&self) -> f64 {
-        self.line_width
+    pub fn seed(&self) -> u64 {
+        self.engine.seed()
     }
 
-    #[wasm_bindgen(getter)]
-    pub fn is_grown(&self) -> bool {
-        self.is_grown
+    /// Serialize the complete engine state to a compact binary snapshot
+    pub fn snapshot(&self) -> Vec<u8> {
+        self.engine.snapshot()
     }
-}
 
-pub struct Lightning {
-    segments: Vec<Point>,
-    branches: Vec<Vec<Point>>,
-    color: String,
-    line_width: f64,
-    life: f64,
-    decay: f64,
-}
+    /// Rebuild an engine from `snapshot` output
+    pub fn from_snapshot(bytes: &[u8]) -> Result<DigitalBloom, JsValue> {
+        let engine = digital_bloom_core::DigitalBloom::from_snapshot(bytes).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(engine.into())
+    }
 
-impl Lightning {
-    pub fn new(start_x: f64, start_y: f64, end_x: f64, end_y: f64, color: String) -> Self {
-        let mut segments = Vec::new();
-        let mut branches = Vec::new();
-
-        // Generate main bolt with jagged segments
-        let segment_count = 15;
-        for i in 0..=segment_count {
-            let t = i as f64 / segment_count as f64;
-            let x = start_x + (end_x - start_x) * t;
-            let y = start_y + (end_y - start_y) * t;
-
-            // Add perpendicular offset for jagged effect
-            let offset = (random() - 0.5) * 30.0;
-            let dx = end_y - start_y;
-            let dy = -(end_x - start_x);
-            let len = (dx * dx + dy * dy).sqrt();
-            let nx = dx / len;
-            let ny = dy / len;
-
-            segments.push(Point {
-                x: x + nx * offset,
-                y: y + ny * offset,
-            });
-        }
+    /// Replace the engine state with `snapshot` output; the state is untouched on error
+    pub fn restore(&mut self, bytes: &[u8]) -> Result<(), JsValue> {
+        self.engine.restore(bytes).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
 
-        // Generate 2-4 branches from random points
-        let branch_count = (random() * 3.0).floor() as usize + 2;
-        for _ in 0..branch_count {
-            let branch_start_idx = (random() * (segments.len() as f64 * 0.7)).floor() as usize + 1;
-            if branch_start_idx >= segments.len() {
-                continue;
-            }
-
-            let start_point = &segments[branch_start_idx];
-            let branch_length = 5 + (random() * 5.0).floor() as usize;
-            let mut branch = Vec::new();
-            branch.push(start_point.clone());
-
-            let branch_angle = random() * PI * 2.0;
-            for i in 1..=branch_length {
-                let dist = i as f64 * 8.0;
-                let offset = (random() - 0.5) * 15.0;
-                branch.push(Point {
-                    x: start_point.x + branch_angle.cos() * dist + offset,
-                    y: start_point.y + branch_angle.sin() * dist + offset,
-                });
-            }
-            branches.push(branch);
-        }
+    /// Serialize the complete engine state as human-readable JSON
+    pub fn snapshot_json(&self) -> String {
+        self.engine.snapshot_json()
+    }
 
-        Lightning {
-            segments,
-            branches,
-            color,
-            line_width: random() * 2.0 + 1.5,
-            life: 1.0,
-            decay: 0.02,
-        }
+    /// Replace the engine state with `snapshot_json` output; the state is untouched on error
+    pub fn restore_json(&mut self, json: &str) -> Result<(), JsValue> {
+        self.engine.restore_json(json).map_err(|e| JsValue::from_str(&e.to_string()))
     }
 
-    pub fn update(&mut self) -> bool {
-        self.life -= self.decay;
-        self.life > 0.0
+    /// Start logging every call made on this engine, discarding any previous log
+    pub fn start_recording(&mut self) {
+        self.engine.start_recording();
     }
 
-    pub fn to_js(&self) -> JsLightning {
-        JsLightning {
-            segments: self.segments.clone(),
-            branches: self.branches.clone(),
-            color: self.color.clone(),
-            line_width: self.line_width * self.life,
-            life: self.life,
-        }
+    /// Stop logging and return the recording bytes, or `undefined` if none was running
+    pub fn stop_recording(&mut self) -> Option<Vec<u8>> {
+        self.engine.stop_recording().map(|recording| recording.to_bytes())
     }
-}
 
-#[wasm_bindgen]
-pub struct DigitalBloom {
-    vines: Vec<Vine>,
-    grown_vines: Vec<Vine>,
-    particles: Vec<Particle>,
-    lightnings: Vec<Lightning>,
-    colors: Vec<String>,
-    max_length: f64,
-    min_length: f64,
-    max_particles: usize,
-    max_lightnings: usize,
-}
+    #[wasm_bindgen(getter)]
+    pub fn is_recording(&self) -> bool {
+        self.engine.is_recording()
+    }
 
-#[wasm_bindgen]
-impl DigitalBloom {
-    #[wasm_bindgen(constructor)]
-    pub fn new() -> DigitalBloom {
-        let colors: Vec<String> = vec![
-            "#ff69b4".to_string(),
-            "#00ffff".to_string(),
-            "#7fff00".to_string(),
-            "#ff00ff".to_string(),
-            "#ff8c00".to_string(),
-            "#adff2f".to_string(),
-            "#d8bfd8".to_string(),
-        ];
+    /// Create an engine by replaying `stop_recording` output
+    pub fn replay(bytes: &[u8]) -> Result<DigitalBloom, JsValue> {
+        let engine = Recording::from_bytes(bytes)
+            .and_then(|recording| recording.replay())
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(engine.into())
+    }
 
-        DigitalBloom {
-            vines: Vec::new(),
-            grown_vines: Vec::new(),
-            particles: Vec::new(),
-            lightnings: Vec::new(),
-            colors,
-            max_length: 200.0,
-            min_length: 50.0,
-            max_particles: 500,
-            max_lightnings: 20,
-        }
+    /// Spawn elements for any brush mode; lightning uses the current canvas size
+    pub fn create_particles(&mut self, mode: ParticleMode, x: f64, y: f64, count: usize, size: f64) {
+        self.engine.create(mode, x, y, count, size);
     }
 
     pub fn create_vine(&mut self, x: f64, y: f64, size: f64) {
-        let color = self.colors[(random() * self.colors.len() as f64).floor() as usize].clone();
-        let mut vine = Vine::new();
-        vine.init(x, y, color, size, self.max_length, self.min_length);
-        self.vines.push(vine);
+        self.engine.create_vine(x, y, size);
     }
 
     pub fn create_particles_gravity(&mut self, x: f64, y: f64, count: usize, size: f64) {
-        let color = self.colors[(random() * self.colors.len() as f64).floor() as usize].clone();
-        for _ in 0..count {
-            let vx = (random() - 0.5) * 4.0;
-            let vy = -random() * 5.0 - 2.0;
-            let particle = Particle::new(x, y, vx, vy, color.clone(), size * 0.5, "gravity".to_string());
-            self.particles.push(particle);
-        }
-        self.limit_particles();
+        self.engine.create_particles_gravity(x, y, count, size);
     }
 
     pub fn create_particles_burst(&mut self, x: f64, y: f64, count: usize, size: f64) {
-        let color = self.colors[(random() * self.colors.len() as f64).floor() as usize].clone();
-        for i in 0..count {
-            let angle = (PI * 2.0 / count as f64) * i as f64;
-            let speed = random() * 6.0 + 2.0;
-            let vx = angle.cos() * speed;
-            let vy = angle.sin() * speed;
-            let particle_size = size * (random() * 0.5 + 0.5) * 0.5;
-            let particle = Particle::new(x, y, vx, vy, color.clone(), particle_size, "burst".to_string());
-            self.particles.push(particle);
-        }
-        self.limit_particles();
+        self.engine.create_particles_burst(x, y, count, size);
     }
 
     pub fn create_particles_bounce(&mut self, x: f64, y: f64, count: usize, size: f64) {
-        let color = self.colors[(random() * self.colors.len() as f64).floor() as usize].clone();
-        for _ in 0..count {
-            let vx = (random() - 0.5) * 8.0;
-            let vy = (random() - 0.5) * 8.0;
-            let particle = Particle::new(x, y, vx, vy, color.clone(), size * 0.5, "bounce".to_string());
-            self.particles.push(particle);
-        }
-        self.limit_particles();
+        self.engine.create_particles_bounce(x, y, count, size);
     }
 
     pub fn create_particles_constellation(&mut self, x: f64, y: f64, count: usize, size: f64) {
-        let color = self.colors[(random() * self.colors.len() as f64).floor() as usize].clone();
-        for _ in 0..count {
-            let offset_x = (random() - 0.5) * 60.0;
-            let offset_y = (random() - 0.5) * 60.0;
-            let vx = (random() - 0.5) * 0.5;
-            let vy = (random() - 0.5) * 0.5;
-            let particle_size = size * (random() * 0.5 + 0.6);
-            let mut particle = Particle::new(x + offset_x, y + offset_y, vx, vy, color.clone(), particle_size, "constellation".to_string());
-            particle.decay = 0.002; // Slower decay for stars
-            self.particles.push(particle);
-        }
-        self.limit_particles();
+        self.engine.create_particles_constellation(x, y, count, size);
     }
 
     pub fn create_particles_vortex(&mut self, x: f64, y: f64, count: usize, size: f64) {
-        let color = self.colors[(random() * self.colors.len() as f64).floor() as usize].clone();
-        for i in 0..count {
-            let angle = (PI * 2.0 / count as f64) * i as f64 + random() * 0.5;
-            let radius = random() * 80.0 + 40.0;
-            let particle_x = x + angle.cos() * radius;
-            let particle_y = y + angle.sin() * radius;
-            let particle = Particle::new_vortex(particle_x, particle_y, x, y, angle, radius, color.clone(), size * 0.6);
-            self.particles.push(particle);
-        }
-        self.limit_particles();
+        self.engine.create_particles_vortex(x, y, count, size);
     }
 
-    pub fn create_lightning(&mut self, x: f64, y: f64, _width: f64, height: f64) {
-        // Generate random endpoint for lightning bolt
-        let end_x = x + (random() - 0.5) * 300.0;
-        let end_y = y + (random() * 0.6 + 0.2) * height * 0.5; // Prefer downward bolts
+    /// Launch a shell that bursts into `count` sparks at the top of its climb
+    pub fn create_particles_fireworks(&mut self, x: f64, y: f64, count: usize, size: f64) {
+        self.engine.create_particles_fireworks(x, y, count, size);
+    }
 
-        let color = if random() < 0.5 { "#ffffff".to_string() } else { "#00ffff".to_string() };
-        let lightning = Lightning::new(x, y, end_x, end_y, color);
-        self.lightnings.push(lightning);
-        self.limit_lightnings();
+    /// Release puffs that drift through a curl-noise field, growing as they fade
+    pub fn create_particles_smoke(&mut self, x: f64, y: f64, count: usize, size: f64) {
+        self.engine.create_particles_smoke(x, y, count, size);
     }
 
-    fn limit_lightnings(&mut self) {
-        if self.lightnings.len() > self.max_lightnings {
-            let excess = self.lightnings.len() - self.max_lightnings;
-            self.lightnings.drain(0..excess);
-        }
+    /// Release a flock that steers together, and toward the pointer if one is set
+    pub fn create_particles_boids(&mut self, x: f64, y: f64, count: usize, size: f64) {
+        self.engine.create_particles_boids(x, y, count, size);
     }
 
-    fn limit_particles(&mut self) {
-        if self.particles.len() > self.max_particles {
-            let excess = self.particles.len() - self.max_particles;
-            self.particles.drain(0..excess);
-        }
+    pub fn create_lightning(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.engine.create_lightning(x, y, width, height);
     }
 
     pub fn update(&mut self, width: f64, height: f64) {
-        // Update vines
-        let mut still_active = Vec::new();
-        for mut vine in self.vines.drain(..) {
-            if vine.update(width, height) {
-                still_active.push(vine);
-            } else {
-                self.grown_vines.push(vine);
-            }
-        }
-        self.vines = still_active;
+        self.engine.update(width, height);
+    }
 
-        let mut still_fading = Vec::new();
-        for mut vine in self.grown_vines.drain(..) {
-            if vine.fade() {
-                still_fading.push(vine);
-            }
-        }
-        self.grown_vines = still_fading;
-
-        // Update particles
-        let mut active_particles = Vec::new();
-        for mut particle in self.particles.drain(..) {
-            if particle.update(width, height) {
-                active_particles.push(particle);
-            }
-        }
-        self.particles = active_particles;
-
-        // Update lightning
-        let mut active_lightnings = Vec::new();
-        for mut lightning in self.lightnings.drain(..) {
-            if lightning.update() {
-                active_lightnings.push(lightning);
-            }
-        }
-        self.lightnings = active_lightnings;
+    /// Advance by `dt_seconds` of wall-clock time in fixed 1/60s steps; returns the step count
+    pub fn update_dt(&mut self, dt_seconds: f64) -> u32 {
+        self.engine.update_dt(dt_seconds)
+    }
+
+    /// Set the canvas size used by `update_dt` and `create_particles`
+    pub fn set_bounds(&mut self, width: f64, height: f64) {
+        self.engine.set_bounds(width, height);
+    }
+
+    /// Draw boids toward (x, y) until `clear_pointer` is called
+    pub fn set_pointer(&mut self, x: f64, y: f64) {
+        self.engine.set_pointer(x, y);
+    }
+
+    pub fn clear_pointer(&mut self) {
+        self.engine.clear_pointer();
+    }
+
+    /// Interpolation factor for drawing particles between `prev_x/prev_y` and `x/y`
+    #[wasm_bindgen(getter)]
+    pub fn alpha(&self) -> f64 {
+        self.engine.alpha()
     }
 
     #[wasm_bindgen(getter)]
     pub fn vines(&self) -> JsValue {
-        let js_vines: Vec<JsVine> = self.vines.iter().map(|v| JsVine {
-            points: v.points.clone(),
-            color: v.color.clone(),
-            line_width: v.line_width,
-        }).collect();
+        let js_vines: Vec<JsVine> = self.engine.vines_slice().iter().map(vine_to_js).collect();
         serde_wasm_bindgen::to_value(&js_vines).unwrap()
     }
 
     #[wasm_bindgen(getter)]
     pub fn grown_vines(&self) -> JsValue {
-        let js_vines: Vec<JsVine> = self.grown_vines.iter().map(|v| JsVine {
-            points: v.points.clone(),
-            color: v.color.clone(),
-            line_width: v.line_width,
-        }).collect();
+        let js_vines: Vec<JsVine> = self.engine.grown_vines_slice().iter().map(vine_to_js).collect();
         serde_wasm_bindgen::to_value(&js_vines).unwrap()
     }
 
     #[wasm_bindgen(getter)]
     pub fn particles(&self) -> JsValue {
-        let js_particles: Vec<JsParticle> = self.particles.iter().map(|p| p.to_js()).collect();
+        let js_particles: Vec<JsParticle> = self.engine.particles().iter().map(|p| particle_to_js(&p)).collect();
         serde_wasm_bindgen::to_value(&js_particles).unwrap()
     }
 
+    /// Line segments joining nearby constellation stars; draw them before `particles`
+    #[wasm_bindgen(getter)]
+    pub fn links(&self) -> JsValue {
+        let js_links: Vec<JsLink> = self.engine.links().iter().map(link_to_js).collect();
+        serde_wasm_bindgen::to_value(&js_links).unwrap()
+    }
+
     #[wasm_bindgen(getter)]
     pub fn lightnings(&self) -> JsValue {
-        let js_lightnings: Vec<JsLightning> = self.lightnings.iter().map(|l| l.to_js()).collect();
+        let js_lightnings: Vec<JsLightning> = self.engine.lightnings_slice().iter().map(lightning_to_js).collect();
         serde_wasm_bindgen::to_value(&js_lightnings).unwrap()
     }
 
-    pub fn clear(&mut self) {
-        self.vines.clear();
-        self.grown_vines.clear();
-        self.particles.clear();
-        self.lightnings.clear();
+    /// Export the current scene as a `width` x `height` SVG document
+    pub fn to_svg(&self, width: f64, height: f64) -> String {
+        self.engine.to_svg(width, height)
     }
 
-    // FFI-friendly getter methods
-    pub fn particles_slice(&self) -> &[Particle] {
-        &self.particles
+    /// Export as SVG with `{ background?: "#1a1a24" | null, glow?: boolean }`
+    pub fn to_svg_with(&self, width: f64, height: f64, options: JsValue) -> Result<String, JsValue> {
+        let options: SvgOptions = serde_wasm_bindgen::from_value(options)?;
+        Ok(self.engine.to_svg_with(width, height, &options))
+    }
+
+    /// Repack the scene into the typed-array render buffers
+    ///
+    /// Call once per frame after updating, then read the `*_buffer` views. The
+    /// views point straight into wasm memory: re-fetch them after every pack,
+    /// since any engine call may move or grow that memory.
+    pub fn pack_render_buffers(&mut self) {
+        self.buffers.fill(&self.engine);
+    }
+
+    /// Floats per particle in `particle_buffer`: x, y, prev_x, prev_y, size, life
+    pub fn particle_stride() -> usize {
+        PARTICLE_STRIDE
+    }
+
+    /// u32s per path in `path_buffer`: point offset, point count, kind
+    /// (0 vine, 1 grown vine, 2 lightning, 3 lightning branch)
+    pub fn path_stride() -> usize {
+        PATH_STRIDE
+    }
+
+    pub fn particle_buffer(&self) -> js_sys::Float32Array {
+        // SAFETY: the view is only valid until the next call into wasm, as documented on pack_render_buffers
+        unsafe { js_sys::Float32Array::view(&self.buffers.particles) }
+    }
+
+    /// r, g, b, a per particle
+    pub fn particle_color_buffer(&self) -> js_sys::Uint8Array {
+        // SAFETY: see particle_buffer
+        unsafe { js_sys::Uint8Array::view(&self.buffers.particle_colors) }
+    }
+
+    /// x, y per point for every vine and lightning path
+    pub fn path_point_buffer(&self) -> js_sys::Float32Array {
+        // SAFETY: see particle_buffer
+        unsafe { js_sys::Float32Array::view(&self.buffers.path_points) }
+    }
+
+    pub fn path_buffer(&self) -> js_sys::Uint32Array {
+        // SAFETY: see particle_buffer
+        unsafe { js_sys::Uint32Array::view(&self.buffers.paths) }
+    }
+
+    /// line width, life per path; life is 1 - fade progress for vines
+    pub fn path_style_buffer(&self) -> js_sys::Float32Array {
+        // SAFETY: see particle_buffer
+        unsafe { js_sys::Float32Array::view(&self.buffers.path_styles) }
+    }
+
+    /// r, g, b, a per path
+    pub fn path_color_buffer(&self) -> js_sys::Uint8Array {
+        // SAFETY: see particle_buffer
+        unsafe { js_sys::Uint8Array::view(&self.buffers.path_colors) }
+    }
+
+    pub fn clear(&mut self) {
+        self.engine.clear();
     }
 
     pub fn particles_len(&self) -> usize {
-        self.particles.len()
+        self.engine.particles_len()
+    }
+
+    /// Indices of the particles within `radius` of (x, y), in `particles` / `particle_buffer` order
+    pub fn query_radius(&self, x: f64, y: f64, radius: f64) -> Vec<u32> {
+        self.engine.query_radius(x, y, radius).into_iter().map(|i| i as u32).collect()
+    }
+
+    /// Indices of the particles inside the rectangle, edges included
+    pub fn query_rect(&self, min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> Vec<u32> {
+        self.engine.query_rect(min_x, min_y, max_x, max_y).into_iter().map(|i| i as u32).collect()
     }
 
     pub fn set_max_particles(&mut self, max: usize) {
-        self.max_particles = max;
+        self.engine.set_max_particles(max);
+    }
+
+    /// Choose how the particle count is kept in check
+    ///
+    /// `policy` is `{ type: "hard_cap" | "random_cull" | "smallest_first", max: 500 }`,
+    /// `{ type: "dynamic_decay" }` or `{ type: "none" }`.
+    pub fn set_budget_policy(&mut self, policy: JsValue) -> Result<(), JsValue> {
+        let policy: BudgetPolicy = serde_wasm_bindgen::from_value(policy)?;
+        self.engine.set_budget_policy(policy);
+        Ok(())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn budget_policy(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.engine.budget_policy()).unwrap()
+    }
+
+    /// Total on-screen elements: particles plus every vine and lightning point
+    pub fn scene_load(&self) -> usize {
+        self.engine.scene_load()
     }
 }
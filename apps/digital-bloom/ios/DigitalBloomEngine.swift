@@ -40,10 +40,10 @@ public class DigitalBloomEngine {
         public let y: Double
         public let size: Double
         public let life: Double
-        public let color: (r: UInt8, g: UInt8, b: UInt8)
+        public let color: (r: UInt8, g: UInt8, b: UInt8, a: UInt8)
 
         public var opacity: Double {
-            return life
+            return life * Double(color.a) / 255.0
         }
     }
 
@@ -86,7 +86,7 @@ public class DigitalBloomEngine {
     public func getParticles(maxParticles: Int = 500) -> [Particle] {
         guard let engine = rustEngine else { return [] }
 
-        var buffer = [CParticle](repeating: CParticle(x: 0, y: 0, size: 0, life: 0, color_r: 0, color_g: 0, color_b: 0), count: maxParticles)
+        var buffer = [CParticle](repeating: CParticle(x: 0, y: 0, prev_x: 0, prev_y: 0, size: 0, life: 0, color_r: 0, color_g: 0, color_b: 0, color_a: 0), count: maxParticles)
 
         let count = buffer.withUnsafeMutableBufferPointer { bufferPtr -> Int in
             return Int(digital_bloom_get_particles(engine, bufferPtr.baseAddress, UInt(maxParticles)))
@@ -98,7 +98,7 @@ public class DigitalBloomEngine {
                 y: cParticle.y,
                 size: cParticle.size,
                 life: cParticle.life,
-                color: (r: cParticle.color_r, g: cParticle.color_g, b: cParticle.color_b)
+                color: (r: cParticle.color_r, g: cParticle.color_g, b: cParticle.color_b, a: cParticle.color_a)
             )
         }
     }
@@ -522,6 +522,15 @@ interface Lightning {
     life: number;
 }
 
+// Engine colors are #rrggbb, or #rrggbbaa when the palette entry isn't opaque;
+// fold any alpha already in the color into `opacity`
+function withOpacity(color: string, opacity: number): string {
+    const base = color.slice(0, 7);
+    const existing = color.length === 9 ? parseInt(color.slice(7, 9), 16) / 255 : 1;
+    const alpha = Math.floor(Math.min(Math.max(opacity * existing, 0), 1) * 255);
+    return base + alpha.toString(16).padStart(2, '0');
+}
+
 function drawVine(vine: Vine) {
     if (vine.points.length < 2) return;
     ctx.beginPath();
@@ -537,15 +546,14 @@ function drawVine(vine: Vine) {
 function drawParticle(particle: Particle) {
     ctx.beginPath();
     ctx.arc(particle.x, particle.y, particle.size, 0, Math.PI * 2);
-    const alpha = Math.floor(particle.life * 255).toString(16).padStart(2, '0');
-    ctx.fillStyle = particle.color + alpha;
+    ctx.fillStyle = withOpacity(particle.color, particle.life);
     ctx.fill();
 }
 
 function drawLightning(lightning: Lightning) {
     if (lightning.segments.length < 2) return;
 
-    const alpha = Math.floor(lightning.life * 255).toString(16).padStart(2, '0');
+    const stroke = withOpacity(lightning.color, lightning.life);
 
     // Draw main bolt
     ctx.beginPath();
@@ -553,7 +561,7 @@ function drawLightning(lightning: Lightning) {
     for (let i = 1; i < lightning.segments.length; i++) {
         ctx.lineTo(lightning.segments[i].x, lightning.segments[i].y);
     }
-    ctx.strokeStyle = lightning.color + alpha;
+    ctx.strokeStyle = stroke;
     ctx.lineWidth = lightning.line_width;
     ctx.lineCap = 'round';
     ctx.lineJoin = 'round';
@@ -573,7 +581,7 @@ function drawLightning(lightning: Lightning) {
         for (let i = 1; i < branch.length; i++) {
             ctx.lineTo(branch[i].x, branch[i].y);
         }
-        ctx.strokeStyle = lightning.color + alpha;
+        ctx.strokeStyle = stroke;
         ctx.lineWidth = lightning.line_width * 0.6;
         ctx.stroke();
     }